@@ -24,8 +24,8 @@ alias qa quit-all
 alias o open
 alias s save
 alias sa save-all
-alias r reopen
-alias ra reopen-all
+alias r reload
+alias ra reload-all
 alias c close
 alias ca close-all
 
@@ -103,8 +103,8 @@ impl ServerApplication {
                     &config.path,
                     &source,
                 ) {
-                    EditorControlFlow::Continue => (),
-                    _ => return None,
+                    Some(EditorControlFlow::Continue) | None => (),
+                    Some(_) => return None,
                 },
                 Err(_) => editor
                     .status_bar
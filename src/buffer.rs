@@ -1,12 +1,13 @@
 use std::{
     fmt,
-    fs::File,
+    fs::{self, File},
     io,
     num::NonZeroU8,
     ops::RangeBounds,
     path::{Path, PathBuf},
     process::{Command, Stdio},
     str::CharIndices,
+    time::SystemTime,
 };
 
 use crate::{
@@ -440,6 +441,34 @@ impl BufferContent {
         }
     }
 
+    pub fn find_text_from(&self, position: BufferPosition, text: &str) -> Option<BufferRange> {
+        if text.is_empty() || text.contains('\n') {
+            return None;
+        }
+
+        let position = self.saturate_position(position);
+        let line_count = self.line_count();
+        for i in 0..=line_count {
+            let line_index = (position.line_index as usize + i) % line_count;
+            let line = self.line_at(line_index).as_str();
+            let column_index = if i == 0 {
+                let from = position.column_byte_index as usize;
+                line[from..].find(text).map(|c| from + c)
+            } else {
+                line.find(text)
+            };
+
+            if let Some(column_index) = column_index {
+                let from = BufferPosition::line_col(line_index as _, column_index as _);
+                let to =
+                    BufferPosition::line_col(line_index as _, (column_index + text.len()) as _);
+                return Some(BufferRange::between(from, to));
+            }
+        }
+
+        None
+    }
+
     pub fn insert_text(&mut self, position: BufferPosition, text: &str) -> BufferRange {
         if !text.contains('\n') {
             let line = &mut self.lines[position.line_index as usize];
@@ -686,6 +715,23 @@ impl fmt::Display for BufferContent {
     }
 }
 
+pub enum BufferReadError {
+    FileTooBig(u64),
+    IoError(io::Error),
+}
+impl fmt::Display for BufferReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FileTooBig(len) => write!(
+                f,
+                "file is {} bytes which is over the max_open_size config",
+                len
+            ),
+            Self::IoError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct BufferCapabilities {
     pub has_history: bool,
@@ -723,6 +769,8 @@ pub struct Buffer {
     history: History,
     search_ranges: Vec<BufferRange>,
     needs_save: bool,
+    file_modified_time: Option<SystemTime>,
+    reported_changed_on_disk: bool,
     pub capabilities: BufferCapabilities,
 }
 
@@ -738,6 +786,8 @@ impl Buffer {
             history: History::new(),
             search_ranges: Vec::new(),
             needs_save: false,
+            file_modified_time: None,
+            reported_changed_on_disk: false,
             capabilities: BufferCapabilities::default(),
         }
     }
@@ -753,6 +803,8 @@ impl Buffer {
         self.history.clear();
         self.search_ranges.clear();
         self.needs_save = false;
+        self.file_modified_time = None;
+        self.reported_changed_on_disk = false;
         self.capabilities = BufferCapabilities::default();
     }
 
@@ -786,7 +838,14 @@ impl Buffer {
         }
 
         let syntax_handle = syntaxes.find_handle_by_path(path).unwrap_or_default();
+        self.set_syntax(syntax_handle);
+    }
+
+    pub fn syntax_handle(&self) -> SyntaxHandle {
+        self.syntax_handle
+    }
 
+    pub fn set_syntax(&mut self, syntax_handle: SyntaxHandle) {
         if self.syntax_handle != syntax_handle {
             self.syntax_handle = syntax_handle;
             self.highlighted.clear();
@@ -801,10 +860,59 @@ impl Buffer {
         &self.content
     }
 
+    pub fn changed_on_disk(&self) -> bool {
+        self.file_modified_time != file_modified_time(&self.path)
+    }
+
+    pub fn take_changed_on_disk_report(&mut self) -> bool {
+        let report = !self.reported_changed_on_disk;
+        self.reported_changed_on_disk = true;
+        report
+    }
+
     pub fn needs_save(&self) -> bool {
         self.capabilities.can_save && self.needs_save
     }
 
+    pub fn trim_trailing_whitespace(
+        &mut self,
+        word_database: &mut WordDatabase,
+        events: &mut EditorEventQueue,
+    ) {
+        for line_index in (0..self.content.line_count()).rev() {
+            let line = self.content.line_at(line_index).as_str();
+            let trimmed_len = line.trim_end_matches(&[' ', '\t'][..]).len();
+            if trimmed_len < line.len() {
+                let range = BufferRange::between(
+                    BufferPosition::line_col(line_index as _, trimmed_len as _),
+                    BufferPosition::line_col(line_index as _, line.len() as _),
+                );
+                self.delete_range(word_database, range, events);
+            }
+        }
+    }
+
+    pub fn trim_trailing_blank_lines(
+        &mut self,
+        word_database: &mut WordDatabase,
+        events: &mut EditorEventQueue,
+    ) {
+        let last_line_index = self.content.line_count() - 1;
+        let mut line_index = last_line_index;
+        while line_index > 0 && self.content.line_at(line_index).as_str().is_empty() {
+            line_index -= 1;
+        }
+
+        if line_index < last_line_index {
+            let line_len = self.content.line_at(line_index).as_str().len();
+            let range = BufferRange::between(
+                BufferPosition::line_col(line_index as _, line_len as _),
+                BufferPosition::line_col(last_line_index as _, 0),
+            );
+            self.delete_range(word_database, range, events);
+        }
+    }
+
     pub fn insert_text(
         &mut self,
         word_database: &mut WordDatabase,
@@ -1088,6 +1196,8 @@ impl Buffer {
 
         self.capabilities.can_save = true;
         self.needs_save = false;
+        self.file_modified_time = file_modified_time(&self.path);
+        self.reported_changed_on_disk = false;
 
         events.enqueue(EditorEvent::BufferSave {
             handle: self.handle,
@@ -1100,7 +1210,20 @@ impl Buffer {
         &mut self,
         word_database: &mut WordDatabase,
         events: &mut EditorEventQueue,
-    ) -> io::Result<()> {
+        max_size: u64,
+    ) -> Result<(), BufferReadError> {
+        let help_reader = help::open(&self.path);
+        let mut file = None;
+        if help_reader.is_none() {
+            if let Ok(f) = File::open(&self.path) {
+                let len = f.metadata().map(|m| m.len()).unwrap_or(0);
+                if len > max_size {
+                    return Err(BufferReadError::FileTooBig(len));
+                }
+                file = Some(f);
+            }
+        }
+
         self.history.clear();
         self.search_ranges.clear();
         self.needs_save = false;
@@ -1113,11 +1236,18 @@ impl Buffer {
             handle: self.handle,
         });
 
-        if let Some(mut reader) = help::open(&self.path) {
-            self.content.read(&mut reader)?;
-        } else if let Ok(file) = File::open(&self.path) {
+        self.file_modified_time = None;
+        self.reported_changed_on_disk = false;
+        if let Some(mut reader) = help_reader {
+            self.content
+                .read(&mut reader)
+                .map_err(BufferReadError::IoError)?;
+        } else if let Some(file) = file {
+            self.file_modified_time = file.metadata().and_then(|m| m.modified()).ok();
             let mut reader = io::BufReader::new(file);
-            self.content.read(&mut reader)?;
+            self.content
+                .read(&mut reader)
+                .map_err(BufferReadError::IoError)?;
         }
 
         self.highlighted.on_insert(BufferRange::between(
@@ -1137,6 +1267,13 @@ impl Buffer {
     }
 }
 
+fn file_modified_time(path: &Path) -> Option<SystemTime> {
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct BufferHandle(pub u32);
 
@@ -1746,6 +1883,95 @@ mod tests {
         assert_eq!("c\ndef\ng", &text);
     }
 
+    #[test]
+    fn buffer_content_find_text_from() {
+        let buffer = buffer_from_str("abc def\nxx abc\n");
+        let range = |from: (usize, usize), to: (usize, usize)| {
+            Some(BufferRange::between(
+                BufferPosition::line_col(from.0 as _, from.1 as _),
+                BufferPosition::line_col(to.0 as _, to.1 as _),
+            ))
+        };
+
+        let find = |line, column, text| {
+            buffer.find_text_from(BufferPosition::line_col(line, column), text)
+        };
+        assert_eq!(range((0, 0), (0, 3)), find(0, 0, "abc"));
+        assert_eq!(range((1, 3), (1, 6)), find(0, 3, "abc"));
+        assert_eq!(range((0, 0), (0, 3)), find(1, 6, "abc"));
+        assert_eq!(range((0, 0), (0, 3)), find(1, 4, "abc"));
+        assert_eq!(range((0, 4), (0, 7)), find(1, 0, "def"));
+        assert_eq!(None, find(0, 0, "xyz"));
+        assert_eq!(None, find(0, 0, ""));
+        assert_eq!(None, find(0, 0, "def\nxx"));
+    }
+
+    #[test]
+    fn buffer_changed_on_disk() {
+        let path = std::env::temp_dir().join(format!(
+            "{}_buffer_changed_on_disk_{}",
+            env!("CARGO_PKG_NAME"),
+            std::process::id()
+        ));
+        fs::write(&path, "content").unwrap();
+
+        let mut word_database = WordDatabase::new();
+        let mut events = EditorEventQueue::default();
+        let mut buffer = Buffer::new(BufferHandle(0));
+        buffer.capabilities = BufferCapabilities::text();
+        buffer.path.push(&path);
+        assert!(buffer
+            .discard_and_reload_from_file(&mut word_database, &mut events, u64::MAX)
+            .is_ok());
+        assert!(!buffer.changed_on_disk());
+
+        let modified_time = fs::metadata(&path).unwrap().modified().unwrap();
+        let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_modified(modified_time + std::time::Duration::from_secs(1))
+            .unwrap();
+        drop(file);
+        assert!(buffer.changed_on_disk());
+
+        buffer.save_to_file(None, &mut events).unwrap();
+        assert!(!buffer.changed_on_disk());
+
+        fs::remove_file(&path).unwrap();
+        assert!(buffer.changed_on_disk());
+    }
+
+    #[test]
+    fn insert_process_output_streaming() {
+        let (request_sender, _) = std::sync::mpsc::channel();
+        let mut platform = Platform::new(|| (), request_sender);
+        let mut word_database = WordDatabase::new();
+        let mut events = EditorEventQueue::default();
+        let mut buffers = BufferCollection::default();
+
+        let buffer = buffers.add_new();
+        let handle = buffer.handle();
+        buffer.insert_text(
+            &mut word_database,
+            BufferPosition::zero(),
+            "log\n",
+            &mut events,
+        );
+        let position = buffer.content().end();
+        buffers.spawn_insert_process(&mut platform, Command::new(""), handle, position, None);
+
+        buffers.on_process_output(&mut word_database, 0, b"first\nsec", &mut events);
+        assert_eq!("log\nfirst\n", buffers.get(handle).content().to_string());
+        buffers.on_process_output(&mut word_database, 0, b"ond\nthi", &mut events);
+        assert_eq!(
+            "log\nfirst\nsecond\n",
+            buffers.get(handle).content().to_string()
+        );
+        buffers.on_process_exit(&mut word_database, 0, &mut events);
+        assert_eq!(
+            "log\nfirst\nsecond\nthi",
+            buffers.get(handle).content().to_string()
+        );
+    }
+
     #[test]
     fn buffer_content_word_at() {
         fn col(column: usize) -> BufferPosition {
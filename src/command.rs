@@ -1,12 +1,17 @@
-use std::{collections::VecDeque, fmt, io};
+use std::{
+    collections::VecDeque,
+    fmt, io,
+    path::{Component, Path, PathBuf},
+};
 
 use crate::{
-    buffer::{Buffer, BufferHandle},
+    buffer::{Buffer, BufferHandle, BufferReadError},
     buffer_view::BufferViewHandle,
     client::{ClientHandle, ClientManager},
     config::ParseConfigError,
     editor::{Editor, EditorControlFlow},
-    editor_utils::MessageKind,
+    editor_utils::{MessageKind, ProcessCommandError},
+    events::KeyParseAllError,
     glob::InvalidGlobError,
     keymap::ParseKeyMapError,
     pattern::PatternError,
@@ -16,22 +21,35 @@ use crate::{
 mod builtin;
 
 pub const HISTORY_CAPACITY: usize = 10;
+pub const MAX_EVAL_DEPTH: u8 = 16;
 
 pub enum CommandError {
     NoSuchCommand,
+    UnterminatedBalancedToken,
+    TokenTooLong,
     TooManyArguments,
     TooFewArguments,
     NoTargetClient,
     NoBufferOpened,
     UnsavedChanges,
+    FileChangedOnDisk,
+    BuffersNotSaved(String),
+    InvalidConfigLine(String),
     IoError(io::Error),
+    BufferReadError(BufferReadError),
     ConfigError(ParseConfigError),
+    ProcessCommandError(ProcessCommandError),
     NoSuchColor,
     InvalidColorValue,
     KeyMapError(ParseKeyMapError),
+    KeyParseError(KeyParseAllError),
     PatternError(PatternError),
     InvalidGlob,
+    AliasTooLong,
+    EvalTooDeep,
+    NoCommandToRepeat,
     RecursiveSyntaxBegin,
+    NoSuchSyntax,
     NoCurrentSyntax,
     LspServerNotRunning,
     LspServerNotLogging,
@@ -40,19 +58,31 @@ impl fmt::Display for CommandError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::NoSuchCommand => f.write_str("no such command"),
+            Self::UnterminatedBalancedToken => f.write_str("unterminated '[[' literal"),
+            Self::TokenTooLong => f.write_str("command token too long"),
             Self::TooManyArguments => f.write_str("too many arguments"),
             Self::TooFewArguments => f.write_str("too few arguments"),
             Self::NoTargetClient => f.write_str("no target client"),
             Self::NoBufferOpened => f.write_str("no buffer opened"),
             Self::UnsavedChanges => f.write_str("unsaved changes"),
+            Self::FileChangedOnDisk => f.write_str("file changed on disk since it was loaded"),
+            Self::BuffersNotSaved(errors) => write!(f, "could not save buffers:{}", errors),
+            Self::InvalidConfigLine(error) => f.write_str(error),
             Self::IoError(error) => write!(f, "{}", error),
+            Self::BufferReadError(error) => write!(f, "{}", error),
             Self::ConfigError(error) => write!(f, "{}", error),
+            Self::ProcessCommandError(error) => write!(f, "{}", error),
             Self::NoSuchColor => f.write_str("no such color"),
             Self::InvalidColorValue => f.write_str("invalid color value"),
             Self::KeyMapError(error) => write!(f, "{}", error),
+            Self::KeyParseError(error) => write!(f, "{}", error),
             Self::PatternError(error) => write!(f, "pattern error: {}", error),
             Self::InvalidGlob => write!(f, "{}", InvalidGlobError),
+            Self::AliasTooLong => f.write_str("alias too long"),
+            Self::EvalTooDeep => f.write_str("eval recursion too deep"),
+            Self::NoCommandToRepeat => f.write_str("no command to repeat"),
             Self::RecursiveSyntaxBegin => f.write_str("recursive syntax definition"),
+            Self::NoSuchSyntax => f.write_str("no syntax matches that path"),
             Self::NoCurrentSyntax => {
                 f.write_str("no current syntax. did you forget a `syntax-begin`?")
             }
@@ -74,6 +104,10 @@ pub enum CompletionSource {
 
 pub struct CommandArgs<'command>(CommandTokenizer<'command>);
 impl<'command> CommandArgs<'command> {
+    pub fn peek(&self) -> Option<&'command str> {
+        self.0.clone().next()
+    }
+
     pub fn try_next(&mut self) -> Option<&'command str> {
         self.0.next()
     }
@@ -128,6 +162,32 @@ impl<'state, 'command> CommandContext<'state, 'command> {
         Ok(buffer_handle)
     }
 
+    pub fn resolve_path(&self, path: &str) -> Option<PathBuf> {
+        if !path.starts_with("./") && !path.starts_with("../") {
+            return None;
+        }
+
+        let buffer_handle = self.current_buffer_handle().ok()?;
+        let buffer_path = &self.editor.buffers.get(buffer_handle).path;
+        let buffer_dir = buffer_path.parent().unwrap_or(buffer_path);
+
+        let mut resolved = PathBuf::new();
+        for component in buffer_dir.components().chain(Path::new(path).components()) {
+            match component {
+                Component::CurDir => (),
+                Component::ParentDir => match resolved.components().next_back() {
+                    Some(Component::Normal(_)) => {
+                        resolved.pop();
+                    }
+                    Some(Component::RootDir | Component::Prefix(_)) => (),
+                    _ => resolved.push(component),
+                },
+                _ => resolved.push(component),
+            }
+        }
+        Some(resolved)
+    }
+
     pub fn assert_can_discard_all_buffers(&self) -> Result<(), CommandError> {
         if self.bang || !self.editor.buffers.iter().any(Buffer::needs_save) {
             Ok(())
@@ -145,62 +205,136 @@ impl<'state, 'command> CommandContext<'state, 'command> {
     }
 }
 
+const SEPARATORS: &[char] = &[' ', '\t'];
+
+fn expand_alias(aliased: &str, args: &str, expanded: &mut String) {
+    let mut has_placeholders = false;
+    let mut rest = aliased;
+    while let Some(i) = rest.find('$') {
+        expanded.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+        match rest.chars().next() {
+            Some('*') => {
+                expanded.push_str(args.trim_matches(SEPARATORS));
+                has_placeholders = true;
+                rest = &rest[1..];
+            }
+            Some(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if let Some(arg) = CommandTokenizer(args).nth(index) {
+                    if !arg.is_empty() && !arg.contains(SEPARATORS) {
+                        expanded.push_str(arg);
+                    } else if !arg.contains('\'') {
+                        expanded.push('\'');
+                        expanded.push_str(arg);
+                        expanded.push('\'');
+                    } else if !arg.contains('"') {
+                        expanded.push('"');
+                        expanded.push_str(arg);
+                        expanded.push('"');
+                    } else {
+                        push_balanced_literal(arg, expanded);
+                    }
+                }
+                has_placeholders = true;
+                rest = &rest[1..];
+            }
+            Some('$') => {
+                expanded.push('$');
+                rest = &rest[1..];
+            }
+            _ => expanded.push('$'),
+        }
+    }
+    expanded.push_str(rest);
+
+    if !has_placeholders {
+        expanded.push_str(args);
+    }
+}
+
+fn push_balanced_literal(text: &str, expanded: &mut String) {
+    let mut close = String::from("]]");
+    while text.contains(&close[..]) || text.ends_with(&close[..close.len() - 1]) {
+        close.insert(1, '=');
+    }
+    let equals = &close[1..close.len() - 1];
+
+    expanded.push('[');
+    expanded.push_str(equals);
+    expanded.push('[');
+    expanded.push_str(text);
+    expanded.push_str(&close);
+}
+
+const MAX_TOKEN_SCAN_LEN: usize = u16::MAX as _;
+
+fn parse_balanced_token(s: &str) -> Option<(&str, &str)> {
+    let base = s.as_ptr() as usize;
+    let mut chars = s.chars();
+    let mut depth = 0;
+    loop {
+        if chars.as_str().as_ptr() as usize - base > MAX_TOKEN_SCAN_LEN {
+            return None;
+        }
+        match chars.next()? {
+            '=' => depth += 1,
+            '[' => break,
+            _ => return None,
+        }
+    }
+    let start = chars.as_str().as_ptr() as usize;
+    let mut end = start;
+    let mut ending = false;
+    let mut matched = 0;
+    loop {
+        if chars.as_str().as_ptr() as usize - base > MAX_TOKEN_SCAN_LEN {
+            return None;
+        }
+        match chars.next()? {
+            ']' => {
+                if ending && matched == depth {
+                    break;
+                }
+
+                ending = true;
+                matched = 0;
+                end = chars.as_str().as_ptr() as usize - 1;
+            }
+            '=' => matched += 1,
+            _ => ending = false,
+        }
+    }
+    let rest = chars.as_str();
+    let start = start - base;
+    let end = end - base;
+    let token = &s[start..end];
+
+    Some((token, rest))
+}
+
 #[derive(Clone)]
 pub struct CommandTokenizer<'a>(pub &'a str);
 impl<'a> Iterator for CommandTokenizer<'a> {
     type Item = &'a str;
     fn next(&mut self) -> Option<Self::Item> {
         fn next_literal_end(s: &str) -> usize {
-            match s.find(&[' ', '\t'][..]) {
+            match s.find(SEPARATORS) {
                 Some(i) => i,
                 None => s.len(),
             }
         }
 
-        fn parse_balanced_token(s: &str) -> Option<(&str, &str)> {
-            let mut chars = s.chars();
-            let mut depth = 0;
-            loop {
-                match chars.next()? {
-                    '=' => depth += 1,
-                    '[' => break,
-                    _ => return None,
-                }
-            }
-            let start = chars.as_str().as_ptr() as usize;
-            let mut end = start;
-            let mut ending = false;
-            let mut matched = 0;
-            loop {
-                match chars.next()? {
-                    ']' => {
-                        if ending && matched == depth {
-                            break;
-                        }
-
-                        ending = true;
-                        matched = 0;
-                        end = chars.as_str().as_ptr() as usize - 1;
-                    }
-                    '=' => matched += 1,
-                    _ => (),
-                }
-            }
-            let rest = chars.as_str();
-            let base = s.as_ptr() as usize;
-            let start = start - base;
-            let end = end - base;
-            let token = &s[start..end];
-
-            Some((token, rest))
-        }
-
-        self.0 = self.0.trim_start_matches(&[' ', '\t'][..]);
+        self.0 = self.0.trim_start_matches(SEPARATORS);
 
         match self.0.chars().next()? {
             delim @ ('"' | '\'') => {
                 let rest = &self.0[1..];
-                match rest.find(delim) {
+                let scan_len = rest.len().min(MAX_TOKEN_SCAN_LEN);
+                match rest.as_bytes()[..scan_len]
+                    .iter()
+                    .position(|&b| b == delim as u8)
+                {
                     Some(i) => {
                         let token = &rest[..i];
                         self.0 = &rest[i + 1..];
@@ -231,12 +365,81 @@ impl<'a> Iterator for CommandTokenizer<'a> {
     }
 }
 
+fn check_tokens(command: &str) -> Result<(), CommandError> {
+    let mut tokens = CommandTokenizer(command);
+    loop {
+        let rest = tokens.0.trim_start_matches(SEPARATORS);
+        match rest.chars().next() {
+            Some(delim @ ('"' | '\'')) => {
+                let rest = &rest[1..];
+                if rest.len() > MAX_TOKEN_SCAN_LEN
+                    && !rest.as_bytes()[..MAX_TOKEN_SCAN_LEN].contains(&(delim as u8))
+                    && rest[MAX_TOKEN_SCAN_LEN..].contains(delim)
+                {
+                    return Err(CommandError::TokenTooLong);
+                }
+            }
+            Some('[') => {
+                let token = &rest[1..];
+                if token.trim_start_matches('=').starts_with('[')
+                    && parse_balanced_token(token).is_none()
+                {
+                    if token.len() > MAX_TOKEN_SCAN_LEN {
+                        return Err(CommandError::TokenTooLong);
+                    }
+                    return Err(CommandError::UnterminatedBalancedToken);
+                }
+            }
+            Some(_) => (),
+            None => return Ok(()),
+        }
+        tokens.next();
+    }
+}
+
+fn statement_len(command: &str) -> usize {
+    if command.trim_start().starts_with('#') {
+        return command.find('\n').unwrap_or(command.len());
+    }
+
+    let mut tokens = CommandTokenizer(command);
+    loop {
+        let rest = tokens.0.trim_start_matches(SEPARATORS);
+        let token = match tokens.next() {
+            Some(token) => token,
+            None => return command.len(),
+        };
+        if token.as_ptr() != rest.as_ptr() {
+            continue;
+        }
+
+        let token_start = token.as_ptr() as usize - command.as_ptr() as usize;
+        let token_end = token_start + token.len();
+
+        let (token, line_end) = match token.find('\n') {
+            Some(i) => (&token[..i], Some(token_start + i)),
+            None => (token, None),
+        };
+        let token = token.trim_end_matches('\r');
+        if token == ";" {
+            return token_start;
+        }
+        if let Some(line_end) = line_end {
+            return line_end;
+        }
+        if token.ends_with(';') && command[token_end..].starts_with(SEPARATORS) {
+            return token_end - 1;
+        }
+    }
+}
+
 pub struct BuiltinCommand {
     pub name: &'static str,
     pub completions: &'static [CompletionSource],
     pub func: CommandFn,
 }
 
+#[derive(Clone)]
 struct Alias {
     start: u32,
     from_len: u16,
@@ -255,17 +458,32 @@ impl Alias {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct AliasCollection {
     texts: String,
     aliases: Vec<Alias>,
 }
 impl AliasCollection {
-    pub fn add(&mut self, from: &str, to: &str) {
+    pub fn add(&mut self, from: &str, to: &str) -> bool {
         if from.len() > u16::MAX as _ || to.len() > u16::MAX as _ {
-            return;
+            return false;
         }
 
+        self.remove(from);
+
+        let start = self.texts.len() as _;
+        self.texts.push_str(from);
+        self.texts.push_str(to);
+
+        self.aliases.push(Alias {
+            start,
+            from_len: from.len() as _,
+            to_len: to.len() as _,
+        });
+        true
+    }
+
+    pub fn remove(&mut self, from: &str) -> bool {
         for (i, alias) in self.aliases.iter().enumerate() {
             if from == alias.from(&self.texts) {
                 let alias_start = alias.start as usize;
@@ -276,19 +494,11 @@ impl AliasCollection {
                 }
                 self.texts
                     .drain(alias_start..alias_start + alias_len as usize);
-                break;
+                return true;
             }
         }
 
-        let start = self.texts.len() as _;
-        self.texts.push_str(from);
-        self.texts.push_str(to);
-
-        self.aliases.push(Alias {
-            start,
-            from_len: from.len() as _,
-            to_len: to.len() as _,
-        });
+        false
     }
 
     pub fn find(&self, from: &str) -> Option<&str> {
@@ -300,11 +510,18 @@ impl AliasCollection {
 
         None
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases
+            .iter()
+            .map(move |a| (a.from(&self.texts), a.to(&self.texts)))
+    }
 }
 
 pub struct CommandManager {
     builtin_commands: &'static [BuiltinCommand],
     history: VecDeque<String>,
+    eval_depth: u8,
     pub aliases: AliasCollection,
 }
 
@@ -313,6 +530,7 @@ impl CommandManager {
         Self {
             builtin_commands: builtin::COMMANDS,
             history: VecDeque::with_capacity(HISTORY_CAPACITY),
+            eval_depth: 0,
             aliases: AliasCollection::default(),
         }
     }
@@ -321,6 +539,69 @@ impl CommandManager {
         self.builtin_commands.iter().find(|c| c.name == name)
     }
 
+    pub fn check(&self, command: &str) -> Result<(), CommandError> {
+        let mut aliases = self.aliases.clone();
+        self.check_with_aliases(command, &mut aliases)
+    }
+
+    pub fn check_with_aliases(
+        &self,
+        command: &str,
+        aliases: &mut AliasCollection,
+    ) -> Result<(), CommandError> {
+        if command.trim().is_empty() {
+            return Err(CommandError::NoSuchCommand);
+        }
+
+        let mut start = 0;
+        while start < command.len() {
+            let end = start + statement_len(&command[start..]);
+            let statement = command[start..end].trim_end_matches('\r');
+            start = end + 1;
+            if !statement.trim().is_empty() {
+                self.check_statement(statement, aliases)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_statement(
+        &self,
+        command: &str,
+        aliases: &mut AliasCollection,
+    ) -> Result<(), CommandError> {
+        if command.trim_start().starts_with('#') {
+            return Ok(());
+        }
+
+        check_tokens(command)?;
+
+        let mut tokens = CommandTokenizer(command);
+        let name = match tokens.next() {
+            Some(name) => name.trim_end_matches('!'),
+            None => return Err(CommandError::NoSuchCommand),
+        };
+        let mut aliased_tokens = CommandTokenizer(aliases.find(name).unwrap_or(name));
+        let name = aliased_tokens.next().unwrap_or("").trim_end_matches('!');
+        if self.find_command(name).is_none() {
+            return Err(CommandError::NoSuchCommand);
+        }
+
+        if name == "alias" {
+            let mut args = aliased_tokens.chain(tokens);
+            if let (Some(from), Some(to)) = (args.next(), args.next()) {
+                let from = String::from(from);
+                if to.is_empty() {
+                    aliases.remove(&from);
+                } else {
+                    let to = String::from(to);
+                    aliases.add(&from, &to);
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn builtin_commands(&self) -> &[BuiltinCommand] {
         &self.builtin_commands
     }
@@ -383,12 +664,76 @@ impl CommandManager {
         client_handle: Option<ClientHandle>,
         command: &mut String,
     ) -> Result<EditorControlFlow, CommandError> {
+        if command.contains(&['\n', ';'][..]) {
+            let mut start = 0;
+            while start < command.len() {
+                let end = start + statement_len(&command[start..]);
+                let statement = command[start..end].trim_end_matches('\r');
+                start = end + 1;
+                if statement.trim().is_empty() {
+                    continue;
+                }
+
+                let mut statement = editor.string_pool.acquire_with(statement);
+                let result = Self::try_eval_statement(
+                    editor,
+                    platform,
+                    clients,
+                    client_handle,
+                    &mut statement,
+                );
+                editor.string_pool.release(statement);
+                match result? {
+                    EditorControlFlow::Continue => (),
+                    flow => return Ok(flow),
+                }
+            }
+            return Ok(EditorControlFlow::Continue);
+        }
+
+        Self::try_eval_statement(editor, platform, clients, client_handle, command)
+    }
+
+    pub fn try_eval_nested(
+        editor: &mut Editor,
+        platform: &mut Platform,
+        clients: &mut ClientManager,
+        client_handle: Option<ClientHandle>,
+        command: &str,
+    ) -> Result<EditorControlFlow, CommandError> {
+        if editor.commands.eval_depth >= MAX_EVAL_DEPTH {
+            return Err(CommandError::EvalTooDeep);
+        }
+
+        let mut command = editor.string_pool.acquire_with(command);
+        editor.commands.eval_depth += 1;
+        let result = Self::try_eval(editor, platform, clients, client_handle, &mut command);
+        editor.commands.eval_depth -= 1;
+        editor.string_pool.release(command);
+        result
+    }
+
+    fn try_eval_statement(
+        editor: &mut Editor,
+        platform: &mut Platform,
+        clients: &mut ClientManager,
+        client_handle: Option<ClientHandle>,
+        command: &mut String,
+    ) -> Result<EditorControlFlow, CommandError> {
+        if command.trim_start().starts_with('#') {
+            return Ok(EditorControlFlow::Continue);
+        }
+
         if let Some(alias) = CommandTokenizer(command).next() {
             let alias = alias.trim_end_matches('!');
             if let Some(aliased) = editor.commands.aliases.find(alias) {
                 let start = alias.as_ptr() as usize - command.as_ptr() as usize;
                 let end = start + alias.len();
-                command.replace_range(start..end, aliased);
+
+                let mut expanded = editor.string_pool.acquire();
+                expand_alias(aliased, &command[end..], &mut expanded);
+                command.replace_range(start.., &expanded);
+                editor.string_pool.release(expanded);
             }
         }
 
@@ -402,6 +747,8 @@ impl CommandManager {
         client_handle: Option<ClientHandle>,
         command: &str,
     ) -> Result<EditorControlFlow, CommandError> {
+        check_tokens(command)?;
+
         let mut tokenizer = CommandTokenizer(command);
         let command = match tokenizer.next() {
             Some(command) => command,
@@ -431,6 +778,863 @@ impl CommandManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::{path::PathBuf, sync::mpsc};
+
+    use crate::{
+        buffer::BufferCapabilities, buffer_position::BufferPosition, cursor::Cursor, mode::ModeKind,
+    };
+
+    struct EvalContext {
+        editor: Editor,
+        platform: Platform,
+        clients: ClientManager,
+        client_handle: Option<ClientHandle>,
+    }
+    impl EvalContext {
+        fn new() -> Self {
+            let (request_sender, _) = mpsc::channel();
+            Self {
+                editor: Editor::new(PathBuf::new()),
+                platform: Platform::new(|| (), request_sender),
+                clients: ClientManager::default(),
+                client_handle: None,
+            }
+        }
+
+        fn with_buffer(text: &str) -> Self {
+            let mut ctx = Self::new();
+            let client_handle = ClientHandle::from_index(0).unwrap();
+            ctx.clients.on_client_joined(client_handle);
+            ctx.client_handle = Some(client_handle);
+
+            let buffer = ctx.editor.buffers.add_new();
+            buffer.capabilities = BufferCapabilities::text();
+            buffer.insert_text(
+                &mut ctx.editor.word_database,
+                BufferPosition::zero(),
+                text,
+                &mut ctx.editor.events,
+            );
+            let buffer_handle = buffer.handle();
+            ctx.editor
+                .trigger_event_handlers(&mut ctx.platform, &mut ctx.clients);
+
+            let buffer_view_handle = ctx
+                .editor
+                .buffer_views
+                .add_new(client_handle, buffer_handle);
+            ctx.clients
+                .get_mut(client_handle)
+                .set_buffer_view_handle(Some(buffer_view_handle), &mut ctx.editor.events);
+            ctx
+        }
+
+        fn eval(&mut self, command: &str) -> Result<EditorControlFlow, CommandError> {
+            let mut command = command.to_string();
+            CommandManager::try_eval(
+                &mut self.editor,
+                &mut self.platform,
+                &mut self.clients,
+                self.client_handle,
+                &mut command,
+            )
+        }
+    }
+
+    #[test]
+    fn eval_comments() {
+        let mut ctx = EvalContext::new();
+        assert!(matches!(ctx.eval("#quit"), Ok(EditorControlFlow::Continue)));
+        assert!(matches!(
+            ctx.eval(" \t# quit"),
+            Ok(EditorControlFlow::Continue)
+        ));
+        assert!(matches!(ctx.eval("quit"), Ok(EditorControlFlow::Quit)));
+        assert!(matches!(
+            ctx.eval("quit #"),
+            Err(CommandError::TooManyArguments)
+        ));
+    }
+
+    #[test]
+    fn print_command() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("print hello 'big world'").is_ok());
+        assert_eq!(
+            (MessageKind::Info, "hello big world"),
+            ctx.editor.status_bar.message()
+        );
+        assert!(ctx.eval("print").is_ok());
+        assert_eq!((MessageKind::Info, ""), ctx.editor.status_bar.message());
+    }
+
+    #[test]
+    fn eval_command() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("eval 'print x'").is_ok());
+        assert_eq!((MessageKind::Info, "x"), ctx.editor.status_bar.message());
+        assert!(ctx.eval("eval [[eval 'print y']]").is_ok());
+        assert_eq!((MessageKind::Info, "y"), ctx.editor.status_bar.message());
+        assert!(matches!(ctx.eval("eval quit"), Ok(EditorControlFlow::Quit)));
+        assert!(matches!(
+            ctx.eval("eval missing"),
+            Err(CommandError::NoSuchCommand)
+        ));
+
+        assert!(ctx.eval("alias loop 'eval loop'").is_ok());
+        assert!(matches!(ctx.eval("loop"), Err(CommandError::EvalTooDeep)));
+        assert_eq!(0, ctx.editor.commands.eval_depth);
+    }
+
+    #[test]
+    fn trim_whitespace_command() {
+        let mut ctx = EvalContext::with_buffer("a  \nb\t\n  c d \t \ne");
+        let handle = ctx.clients.get(ctx.client_handle.unwrap());
+        let handle = handle.buffer_view_handle().unwrap();
+        let mut cursors = ctx.editor.buffer_views.get_mut(handle).cursors.mut_guard();
+        cursors.clear();
+        cursors.add(Cursor {
+            anchor: BufferPosition::line_col(0, 2),
+            position: BufferPosition::line_col(2, 6),
+        });
+        cursors.add(Cursor {
+            anchor: BufferPosition::line_col(3, 1),
+            position: BufferPosition::line_col(3, 1),
+        });
+        drop(cursors);
+
+        assert!(ctx.eval("trim-whitespace").is_ok());
+        ctx.editor
+            .trigger_event_handlers(&mut ctx.platform, &mut ctx.clients);
+
+        let buffer_view = ctx.editor.buffer_views.get(handle);
+        let content = ctx.editor.buffers.get(buffer_view.buffer_handle).content();
+        let lines: Vec<_> = content.lines().map(|l| l.as_str()).collect();
+        assert_eq!(&["a", "b", "  c d", "e"], &lines[..]);
+
+        let cursors = &buffer_view.cursors[..];
+        assert_eq!(2, cursors.len());
+        assert_eq!(BufferPosition::line_col(0, 1), cursors[0].anchor);
+        assert_eq!(BufferPosition::line_col(2, 5), cursors[0].position);
+        assert_eq!(BufferPosition::line_col(3, 1), cursors[1].position);
+    }
+
+    #[test]
+    fn insert_output_command() {
+        let mut ctx = EvalContext::with_buffer("a\nb");
+        let handle = ctx.clients.get(ctx.client_handle.unwrap());
+        let handle = handle.buffer_view_handle().unwrap();
+        let mut cursors = ctx.editor.buffer_views.get_mut(handle).cursors.mut_guard();
+        cursors.clear();
+        for line_index in 0..2 {
+            let position = BufferPosition::line_col(line_index, 1);
+            cursors.add(Cursor {
+                anchor: position,
+                position,
+            });
+        }
+        drop(cursors);
+
+        assert!(ctx.eval("insert-output 'print x y'").is_ok());
+        assert!(matches!(
+            ctx.eval("insert-output missing"),
+            Err(CommandError::NoSuchCommand)
+        ));
+        assert!(ctx.eval("insert-output [[alias a b]]").is_ok());
+
+        let buffer_view = ctx.editor.buffer_views.get(handle);
+        let content = ctx.editor.buffers.get(buffer_view.buffer_handle).content();
+        let lines: Vec<_> = content.lines().map(|l| l.as_str()).collect();
+        assert_eq!(&["ax y", "bx y"], &lines[..]);
+        assert_eq!("", ctx.editor.status_bar.message().1);
+    }
+
+    #[test]
+    fn buffer_set_syntax() {
+        let mut ctx = EvalContext::with_buffer("echo hi");
+        assert!(ctx.eval("syntax-begin '**/*.sh'").is_ok());
+        assert!(ctx.eval("syntax-keywords echo").is_ok());
+        assert!(ctx.eval("syntax-end").is_ok());
+
+        assert!(matches!(
+            ctx.eval("buffer-set-syntax file.unknown"),
+            Err(CommandError::NoSuchSyntax)
+        ));
+        assert!(ctx.eval("buffer-set-syntax script.sh").is_ok());
+
+        let expected = ctx.editor.syntaxes.find_handle_by_path("script.sh");
+        let buffer = ctx.editor.buffers.iter().next().unwrap();
+        assert!(expected.is_some());
+        assert!(expected == Some(buffer.syntax_handle()));
+    }
+
+    #[test]
+    fn clients_command() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("clients").is_ok());
+        assert_eq!("no clients", ctx.editor.status_bar.message().1);
+
+        let mut ctx = EvalContext::with_buffer("");
+        ctx.editor.buffers.iter_mut().next().unwrap().path = PathBuf::from("file.txt");
+        assert!(ctx.eval("clients").is_ok());
+        assert_eq!(
+            (MessageKind::Info, "0*: file.txt"),
+            ctx.editor.status_bar.message()
+        );
+
+        ctx.clients
+            .on_client_joined(ClientHandle::from_index(1).unwrap());
+        assert!(ctx.eval("clients").is_ok());
+        assert_eq!(
+            "0*: file.txt\n1: no buffer",
+            ctx.editor.status_bar.message().1
+        );
+    }
+
+    #[test]
+    fn buffer_path_and_modified() {
+        let mut ctx = EvalContext::new();
+        assert!(matches!(
+            ctx.eval("buffer-modified"),
+            Err(CommandError::NoTargetClient)
+        ));
+
+        let mut ctx = EvalContext::with_buffer("");
+        let buffer = ctx.editor.buffers.iter_mut().next().unwrap();
+        buffer.path = PathBuf::from("file.txt");
+        assert!(ctx.eval("buffer-path").is_ok());
+        assert_eq!(
+            (MessageKind::Info, "file.txt"),
+            ctx.editor.status_bar.message()
+        );
+        assert!(ctx.eval("buffer-modified").is_ok());
+        assert_eq!((MessageKind::Info, ""), ctx.editor.status_bar.message());
+
+        assert!(ctx.eval("insert-output 'print text'").is_ok());
+        assert!(ctx.eval("buffer-modified").is_ok());
+        assert_eq!((MessageKind::Info, "true"), ctx.editor.status_bar.message());
+    }
+
+    #[test]
+    fn save_message() {
+        let mut ctx = EvalContext::with_buffer("first\nsecond");
+        let path = std::env::temp_dir().join("pepper_save_message_test.txt");
+        let command = format!("save '{}'", path.to_str().unwrap());
+        let result = ctx.eval(&command);
+        let content = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(result.is_ok());
+        assert_eq!("first\nsecond\n", content.unwrap());
+        let message = format!("2 lines written to {:?}", path);
+        assert_eq!(
+            (MessageKind::Info, &message[..]),
+            ctx.editor.status_bar.message()
+        );
+    }
+
+    #[test]
+    fn save_all_reports_every_failure() {
+        let mut ctx = EvalContext::new();
+        let dir = std::env::temp_dir();
+        let paths = [
+            dir.join("pepper_save_all_test_0.txt"),
+            dir.join("pepper_save_all_missing_dir").join("file.txt"),
+            dir.join("pepper_save_all_test_1.txt"),
+        ];
+        for path in &paths {
+            let buffer = ctx.editor.buffers.add_new();
+            buffer.capabilities = BufferCapabilities::text();
+            buffer.path = path.clone();
+        }
+
+        let result = ctx.eval("save-all!");
+        let saved = [paths[0].exists(), paths[2].exists()];
+        let _ = std::fs::remove_file(&paths[0]);
+        let _ = std::fs::remove_file(&paths[2]);
+
+        assert_eq!([true, true], saved);
+        match result {
+            Err(CommandError::BuffersNotSaved(errors)) => {
+                assert_eq!(1, errors.lines().filter(|l| !l.is_empty()).count());
+                assert!(errors.contains("pepper_save_all_missing_dir"));
+            }
+            _ => panic!("expected BuffersNotSaved error"),
+        }
+    }
+
+    #[test]
+    fn save_all_skips_changed_on_disk() {
+        let mut ctx = EvalContext::new();
+        let dir = std::env::temp_dir();
+        let paths = [
+            dir.join("pepper_save_all_skip_test_0.txt"),
+            dir.join("pepper_save_all_skip_test_1.txt"),
+        ];
+        for path in &paths {
+            std::fs::write(path, "disk").unwrap();
+            let buffer = ctx.editor.buffers.add_new();
+            buffer.capabilities = BufferCapabilities::text();
+            buffer.path = path.clone();
+            assert!(buffer
+                .discard_and_reload_from_file(
+                    &mut ctx.editor.word_database,
+                    &mut ctx.editor.events,
+                    u64::MAX,
+                )
+                .is_ok());
+            buffer.insert_text(
+                &mut ctx.editor.word_database,
+                BufferPosition::zero(),
+                "edit ",
+                &mut ctx.editor.events,
+            );
+        }
+        let modified_time = std::fs::metadata(&paths[1]).unwrap().modified().unwrap();
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&paths[1])
+            .unwrap();
+        file.set_modified(modified_time + std::time::Duration::from_secs(1))
+            .unwrap();
+        drop(file);
+
+        let result = ctx.eval("save-all");
+        let contents = [
+            std::fs::read_to_string(&paths[0]),
+            std::fs::read_to_string(&paths[1]),
+        ];
+        let forced = ctx.eval("save-all!");
+        let forced_content = std::fs::read_to_string(&paths[1]);
+        let _ = std::fs::remove_file(&paths[0]);
+        let _ = std::fs::remove_file(&paths[1]);
+
+        match result {
+            Err(CommandError::BuffersNotSaved(errors)) => {
+                assert_eq!(1, errors.lines().filter(|l| !l.is_empty()).count());
+                assert!(errors.contains("pepper_save_all_skip_test_1"));
+                assert!(errors.contains("changed on disk"));
+            }
+            _ => panic!("expected BuffersNotSaved error"),
+        }
+        assert_eq!("edit disk\n", contents[0].as_ref().unwrap());
+        assert_eq!("disk", contents[1].as_ref().unwrap());
+        assert!(forced.is_ok());
+        assert_eq!("edit disk\n", forced_content.unwrap());
+    }
+
+    #[test]
+    fn repeat_last_command() {
+        let mut ctx = EvalContext::with_buffer("");
+        assert!(matches!(
+            ctx.eval("repeat-last-command"),
+            Err(CommandError::NoCommandToRepeat)
+        ));
+
+        ctx.editor.commands.add_to_history("execute-keys ia<esc>");
+        assert!(ctx.eval("execute-keys ia<esc>").is_ok());
+        ctx.editor.commands.add_to_history("repeat-last-command");
+        assert!(ctx.eval("repeat-last-command").is_ok());
+        assert!(ctx.eval("repeat-last-command").is_ok());
+        assert_eq!(2, ctx.editor.commands.history_len());
+
+        let handle = ctx.clients.get(ctx.client_handle.unwrap());
+        let handle = handle.buffer_view_handle().unwrap();
+        let buffer_handle = ctx.editor.buffer_views.get(handle).buffer_handle;
+        let content = ctx.editor.buffers.get(buffer_handle).content();
+        assert_eq!("aaa", content.line_at(0).as_str());
+    }
+
+    #[test]
+    fn insert_mode_command() {
+        let mut ctx = EvalContext::new();
+        assert!(matches!(
+            ctx.eval("insert-mode"),
+            Err(CommandError::NoTargetClient)
+        ));
+
+        let mut ctx = EvalContext::with_buffer("line");
+        assert_eq!(ModeKind::Normal, ctx.editor.mode.kind());
+        assert!(ctx.eval("insert-mode").is_ok());
+        assert_eq!(ModeKind::Insert, ctx.editor.mode.kind());
+
+        assert!(ctx.eval("execute-keys gl").is_ok());
+        assert!(ctx.eval("insert-mode ' end'").is_ok());
+        assert_eq!(ModeKind::Insert, ctx.editor.mode.kind());
+        let handle = ctx.clients.get(ctx.client_handle.unwrap());
+        let handle = handle.buffer_view_handle().unwrap();
+        let buffer_handle = ctx.editor.buffer_views.get(handle).buffer_handle;
+        let content = ctx.editor.buffers.get(buffer_handle).content();
+        assert_eq!("line end", content.line_at(0).as_str());
+    }
+
+    #[test]
+    fn execute_keys_command() {
+        let mut ctx = EvalContext::with_buffer("first\nsecond\nthird");
+        let cursor_position = |ctx: &EvalContext| {
+            let handle = ctx.clients.get(ctx.client_handle.unwrap());
+            let handle = handle.buffer_view_handle().unwrap();
+            let buffer_view = ctx.editor.buffer_views.get(handle);
+            buffer_view.cursors.main_cursor().position
+        };
+
+        assert!(ctx.eval("execute-keys jjl").is_ok());
+        assert_eq!(BufferPosition::line_col(2, 1), cursor_position(&ctx));
+        assert_eq!(ModeKind::Normal, ctx.editor.mode.kind());
+
+        assert!(ctx.eval("execute-keys '2k:print nested<enter>'").is_ok());
+        assert_eq!(BufferPosition::line_col(0, 1), cursor_position(&ctx));
+        assert_eq!("nested", ctx.editor.status_bar.message().1);
+
+        assert!(matches!(
+            ctx.eval("execute-keys <invalid>"),
+            Err(CommandError::KeyParseError(_))
+        ));
+    }
+
+    #[test]
+    fn execute_keys_recursion() {
+        let mut ctx = EvalContext::with_buffer("");
+        assert!(ctx.eval("alias loop 'execute-keys :loop<enter>'").is_ok());
+        assert!(ctx.eval("loop").is_ok());
+        assert_eq!(
+            (MessageKind::Error, "eval recursion too deep"),
+            ctx.editor.status_bar.message()
+        );
+        assert_eq!(0, ctx.editor.commands.eval_depth);
+
+        ctx.editor.commands.eval_depth = MAX_EVAL_DEPTH;
+        assert!(matches!(ctx.eval("loop"), Err(CommandError::EvalTooDeep)));
+        ctx.editor.commands.eval_depth = 0;
+    }
+
+    #[test]
+    fn save_final_newline() {
+        let path = std::env::temp_dir().join("pepper_save_final_newline_test.txt");
+        let save = format!("save '{}'", path.to_str().unwrap());
+
+        let mut ctx = EvalContext::with_buffer("first\nlast");
+        assert!(ctx.eval(&save).is_ok());
+        let missing_newline = std::fs::read_to_string(&path).unwrap();
+
+        let mut ctx = EvalContext::with_buffer("first\nlast\n\n\n");
+        assert!(ctx.eval(&save).is_ok());
+        let kept_blank_lines = std::fs::read_to_string(&path).unwrap();
+
+        ctx.editor.config.trim_trailing_blank_lines = true;
+        assert!(ctx.eval(&save).is_ok());
+        let trimmed_blank_lines = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!("first\nlast\n", missing_newline);
+        assert_eq!("first\nlast\n\n\n\n", kept_blank_lines);
+        assert_eq!("first\nlast\n", trimmed_blank_lines);
+    }
+
+    #[test]
+    fn save_trim_is_its_own_undo_step() {
+        let path = std::env::temp_dir().join("pepper_save_trim_undo_test.txt");
+        let save = format!("save '{}'", path.to_str().unwrap());
+
+        let mut ctx = EvalContext::with_buffer("a\n\n");
+        ctx.editor.config.trim_trailing_blank_lines = true;
+        assert!(ctx.eval(&save).is_ok());
+        let _ = std::fs::remove_file(&path);
+
+        let editor = &mut ctx.editor;
+        let buffer = editor.buffers.iter_mut().next().unwrap();
+        buffer.insert_text(
+            &mut editor.word_database,
+            BufferPosition::line_col(0, 1),
+            "b",
+            &mut editor.events,
+        );
+        buffer.commit_edits();
+        assert_eq!(
+            1,
+            buffer
+                .undo(&mut editor.word_database, &mut editor.events)
+                .len()
+        );
+        assert_eq!(1, buffer.content().line_count());
+        assert_eq!("a", buffer.content().line_at(0).as_str());
+    }
+
+    #[test]
+    fn save_trim_skips_unsavable_buffers() {
+        let mut ctx = EvalContext::with_buffer("a\n\n");
+        ctx.editor.config.trim_trailing_blank_lines = true;
+        let buffer = ctx.editor.buffers.iter_mut().next().unwrap();
+        buffer.capabilities = BufferCapabilities::log();
+
+        assert!(ctx.eval("save").is_ok());
+        assert!(ctx.eval("save-all").is_ok());
+        let buffer = ctx.editor.buffers.iter().next().unwrap();
+        assert_eq!(3, buffer.content().line_count());
+    }
+
+    #[test]
+    fn open_max_size() {
+        let mut ctx = EvalContext::with_buffer("");
+        let path = std::env::temp_dir().join("pepper_open_max_size_test.txt");
+        std::fs::write(&path, "a".repeat(100)).unwrap();
+        let command = format!("open '{}'", path.to_str().unwrap());
+
+        ctx.editor.config.max_open_size = 99;
+        let too_big = ctx.eval(&command);
+        let buffer_count = ctx.editor.buffers.iter().count();
+        ctx.editor.config.max_open_size = 100;
+        let opened = ctx.eval(&command);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(
+            too_big,
+            Err(CommandError::BufferReadError(BufferReadError::FileTooBig(
+                100
+            )))
+        ));
+        assert_eq!(1, buffer_count);
+        assert!(opened.is_ok());
+        assert_eq!(2, ctx.editor.buffers.iter().count());
+    }
+
+    #[test]
+    fn reload_discards_changes() {
+        let path = std::env::temp_dir().join("pepper_reload_discards_changes_test.txt");
+        std::fs::write(&path, "first\nsecond\nthird\nfourth").unwrap();
+        let mut ctx = EvalContext::with_buffer("");
+        let open = format!("open '{}'", path.to_str().unwrap());
+        let opened = ctx.eval(&open);
+        let edited = ctx.eval("execute-keys jjjlllllix<esc>");
+
+        let handle = ctx.clients.get(ctx.client_handle.unwrap());
+        let view_handle = handle.buffer_view_handle().unwrap();
+        let buffer_handle = ctx.editor.buffer_views.get(view_handle).buffer_handle;
+        let needed_save = ctx.editor.buffers.get(buffer_handle).needs_save();
+        let cursors = &ctx.editor.buffer_views.get(view_handle).cursors;
+        let old_position = cursors.main_cursor().position;
+
+        std::fs::write(&path, "new\nend").unwrap();
+        let unsaved = ctx.eval("reload");
+        let reloaded = ctx.eval("reload!");
+        let _ = std::fs::remove_file(&path);
+        ctx.editor
+            .trigger_event_handlers(&mut ctx.platform, &mut ctx.clients);
+
+        assert!(opened.is_ok());
+        assert!(edited.is_ok());
+        assert!(needed_save);
+        assert_eq!(BufferPosition::line_col(3, 6), old_position);
+        assert!(matches!(unsaved, Err(CommandError::UnsavedChanges)));
+        assert!(reloaded.is_ok());
+
+        let buffer = ctx.editor.buffers.get(buffer_handle);
+        assert!(!buffer.needs_save());
+        assert_eq!(2, buffer.content().line_count());
+        assert_eq!("new", buffer.content().line_at(0).as_str());
+        assert_eq!("end", buffer.content().line_at(1).as_str());
+
+        let cursors = &ctx.editor.buffer_views.get(view_handle).cursors;
+        assert_eq!(1, cursors[..].len());
+        assert_eq!(
+            BufferPosition::line_col(1, 3),
+            cursors.main_cursor().position
+        );
+    }
+
+    #[test]
+    fn reload_max_size() {
+        let path = std::env::temp_dir().join("pepper_reload_max_size_test.txt");
+        std::fs::write(&path, "small").unwrap();
+        let mut ctx = EvalContext::with_buffer("");
+        let open = format!("open '{}'", path.to_str().unwrap());
+        let opened = ctx.eval(&open);
+
+        std::fs::write(&path, "a".repeat(100)).unwrap();
+        ctx.editor.config.max_open_size = 99;
+        let too_big = ctx.eval("reload");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(opened.is_ok());
+        assert!(matches!(
+            too_big,
+            Err(CommandError::BufferReadError(BufferReadError::FileTooBig(
+                100
+            )))
+        ));
+        let buffer = ctx.editor.buffers.iter().nth(1).unwrap();
+        assert_eq!("small", buffer.content().line_at(0).as_str());
+    }
+
+    #[test]
+    fn buffer_relative_paths() {
+        let mut ctx = EvalContext::with_buffer("");
+        let buffer_path = Path::new("dir").join("file.txt");
+        ctx.editor.buffers.iter_mut().next().unwrap().path = buffer_path;
+
+        let mut assert_open = |command: &str, expected_path: &Path| {
+            assert!(ctx.eval(command).is_ok());
+            let buffer_view_handle = ctx
+                .clients
+                .get(ctx.client_handle.unwrap())
+                .buffer_view_handle()
+                .unwrap();
+            let buffer_handle = ctx
+                .editor
+                .buffer_views
+                .get(buffer_view_handle)
+                .buffer_handle;
+            assert_eq!(expected_path, ctx.editor.buffers.get(buffer_handle).path);
+            assert!(ctx.eval("open dir/file.txt").is_ok());
+        };
+
+        assert_open("open ../sibling.txt", Path::new("sibling.txt"));
+        assert_open("open ./other.txt", Path::new("dir/other.txt"));
+        assert_open("open .././dir/sub/../other.txt", Path::new("dir/other.txt"));
+        assert_open("open ../../up.txt", Path::new("../up.txt"));
+        assert_open("open cwd.txt", Path::new("cwd.txt"));
+        assert_open("open /abs/../abs.txt", Path::new("/abs/../abs.txt"));
+    }
+
+    #[test]
+    fn now_command() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("now").is_ok());
+        let (kind, message) = ctx.editor.status_bar.message();
+        assert_eq!(MessageKind::Info, kind);
+        assert!(message.parse::<u64>().unwrap() > 0);
+        assert!(matches!(
+            ctx.eval("now %s"),
+            Err(CommandError::TooManyArguments)
+        ));
+    }
+
+    #[test]
+    fn check_commands() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("alias p print").is_ok());
+
+        let commands = &ctx.editor.commands;
+        assert!(commands.check("quit").is_ok());
+        assert!(commands.check("save-all! extra args").is_ok());
+        assert!(commands.check("p hello").is_ok());
+        assert!(commands.check("# anything").is_ok());
+        assert!(matches!(
+            commands.check("invalid-command"),
+            Err(CommandError::NoSuchCommand)
+        ));
+        assert!(matches!(
+            commands.check(""),
+            Err(CommandError::NoSuchCommand)
+        ));
+    }
+
+    #[test]
+    fn source_dir_command() {
+        let dir = std::env::temp_dir().join("pepper_source_dir_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(dir.join("b.pp"), "p from-b\n").unwrap();
+        std::fs::write(dir.join("a.pp"), "alias p print\n").unwrap();
+        std::fs::write(dir.join("c.txt"), "invalid-command\n").unwrap();
+
+        let mut ctx = EvalContext::new();
+        let command = format!("source-dir '{}'", dir.to_str().unwrap());
+        assert!(ctx.eval(&command).is_ok());
+        assert_eq!(
+            (MessageKind::Info, "from-b"),
+            ctx.editor.status_bar.message()
+        );
+
+        std::fs::write(dir.join("c.pp"), "invalid-command\n").unwrap();
+        std::fs::write(dir.join("d.pp"), "print from-d\n").unwrap();
+        assert!(ctx.eval(&command).is_ok());
+        let (kind, message) = ctx.editor.status_bar.message();
+        assert_eq!(MessageKind::Error, kind);
+        assert!(message.contains("c.pp:1\ninvalid-command\n"));
+
+        let missing = dir.join("missing.pp");
+        let command = format!("source! '{}'", missing.to_str().unwrap());
+        assert!(ctx.eval(&command).is_ok());
+        let command = format!("source '{}'", missing.to_str().unwrap());
+        assert!(matches!(ctx.eval(&command), Err(CommandError::IoError(_))));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn check_config_command() {
+        let mut ctx = EvalContext::new();
+        let path = std::env::temp_dir().join("pepper_check_config_test.pp");
+        let command = format!("check-config '{}'", path.to_str().unwrap());
+
+        std::fs::write(&path, "alias x quit\n\n# comment\nprint ok\nx\n").unwrap();
+        let valid = ctx.eval(&command);
+        let valid_message = ctx.editor.status_bar.message().0;
+        std::fs::write(&path, "config tab_size 2\nquit-now\n").unwrap();
+        let invalid = ctx.eval(&command);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(valid, Ok(EditorControlFlow::Continue)));
+        assert_eq!(MessageKind::Info, valid_message);
+        assert_eq!(None, ctx.editor.commands.aliases.find("x"));
+
+        match invalid {
+            Err(CommandError::InvalidConfigLine(message)) => {
+                assert!(message.contains(":2\nquit-now\nno such command"));
+            }
+            _ => panic!("invalid config was not reported"),
+        }
+        assert_eq!(4, ctx.editor.config.tab_size.get());
+
+        let commands = &ctx.editor.commands;
+        assert!(commands.check("alias y alias; y z print; z ok").is_ok());
+        assert!(commands.check("alias y print\nalias y ''\ny").is_err());
+        assert_eq!(None, commands.aliases.find("y"));
+    }
+
+    #[test]
+    fn eval_multiple_lines() {
+        let mut ctx = EvalContext::new();
+        assert!(matches!(
+            ctx.eval("print first\nquit"),
+            Ok(EditorControlFlow::Quit)
+        ));
+        assert_eq!("first", ctx.editor.status_bar.message().1);
+
+        assert!(ctx
+            .eval("alias x print\n\n  # don't\r\nx 'a\nb' [[c\nd]]\n")
+            .is_ok());
+        assert_eq!("a\nb c\nd", ctx.editor.status_bar.message().1);
+
+        assert!(matches!(
+            ctx.eval("print ok\ninvalid-command\nquit"),
+            Err(CommandError::NoSuchCommand)
+        ));
+        assert_eq!("ok", ctx.editor.status_bar.message().1);
+    }
+
+    #[test]
+    fn eval_semicolon_statements() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("alias x print; x 'a;b' [[c;]] d;").is_ok());
+        assert_eq!("a;b c; d;", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("print a;b").is_ok());
+        assert_eq!("a;b", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("print a ;\nprint b;\r\nprint c").is_ok());
+        assert_eq!("c", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("print a;\r\nprint b;").is_ok());
+        assert_eq!("b;", ctx.editor.status_bar.message().1);
+        assert!(matches!(
+            ctx.eval("print first ; quit"),
+            Ok(EditorControlFlow::Quit)
+        ));
+        assert_eq!("first", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("# print a; print b").is_ok());
+        assert_eq!("first", ctx.editor.status_bar.message().1);
+
+        let commands = &ctx.editor.commands;
+        assert!(commands.check("quit; print ;").is_ok());
+        assert!(matches!(
+            commands.check("quit; print;"),
+            Err(CommandError::NoSuchCommand)
+        ));
+        assert!(matches!(
+            commands.check("quit; invalid-command"),
+            Err(CommandError::NoSuchCommand)
+        ));
+    }
+
+    #[test]
+    fn eval_semicolon_ending_argument() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("syntax-begin '**/*.test'").is_ok());
+        assert!(ctx.eval("syntax-symbols a|;").is_ok());
+        assert!(ctx.eval("syntax-symbols a|;\nprint x").is_ok());
+        assert!(ctx.eval("syntax-end").is_ok());
+        assert_eq!(Some("a|;"), CommandTokenizer("syntax-symbols a|;").nth(1));
+        assert_eq!(18, statement_len("syntax-symbols a|;"));
+        assert_eq!(18, statement_len("syntax-symbols a|;\nprint x"));
+        assert_eq!(17, statement_len("syntax-symbols a|; print x"));
+        assert_eq!(15, statement_len("syntax-symbols ; print x"));
+    }
+
+    #[test]
+    fn config_get_and_set() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("config tab_size 7").is_ok());
+        assert!(ctx.eval("config tab_size").is_ok());
+        assert_eq!("7", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("config case_matching sensitive").is_ok());
+        assert!(ctx.eval("config case_matching").is_ok());
+        assert_eq!("sensitive", ctx.editor.status_bar.message().1);
+        assert!(matches!(
+            ctx.eval("config no_such_config"),
+            Err(CommandError::ConfigError(ParseConfigError::NoSuchConfig))
+        ));
+        assert!(matches!(
+            ctx.eval("config tab_size 0"),
+            Err(CommandError::ConfigError(ParseConfigError::InvalidValue))
+        ));
+    }
+
+    #[test]
+    fn color_get_and_set() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("color background 0x1a2b3c").is_ok());
+        assert_eq!(0x1a2b3c, ctx.editor.theme.background.into_u32());
+        assert!(ctx.eval("color background").is_ok());
+        assert_eq!("0x1a2b3c", ctx.editor.status_bar.message().1);
+
+        assert!(ctx.eval("color background ff").is_ok());
+        assert!(ctx.eval("color background").is_ok());
+        assert_eq!("0x0000ff", ctx.editor.status_bar.message().1);
+
+        assert!(matches!(
+            ctx.eval("color no_such_color"),
+            Err(CommandError::NoSuchColor)
+        ));
+        assert!(matches!(
+            ctx.eval("color background 0xzz"),
+            Err(CommandError::InvalidColorValue)
+        ));
+        assert!(matches!(
+            ctx.eval("color background 0x1234567"),
+            Err(CommandError::InvalidColorValue)
+        ));
+        assert_eq!(0xff, ctx.editor.theme.background.into_u32());
+    }
+
+    #[test]
+    fn eval_unterminated_balanced_token() {
+        let mut ctx = EvalContext::new();
+        assert!(matches!(
+            ctx.eval("print [[unterminated"),
+            Err(CommandError::UnterminatedBalancedToken)
+        ));
+        assert!(matches!(
+            ctx.eval("print a [=[b]] c"),
+            Err(CommandError::UnterminatedBalancedToken)
+        ));
+        assert!(ctx.eval("print [[a]] [b [=[c]=]").is_ok());
+        assert_eq!("a [b c", ctx.editor.status_bar.message().1);
+
+        let commands = &ctx.editor.commands;
+        assert!(matches!(
+            commands.check("print [[unterminated"),
+            Err(CommandError::UnterminatedBalancedToken)
+        ));
+    }
+
+    #[test]
+    fn eval_long_command() {
+        let mut ctx = EvalContext::new();
+        let value = "a".repeat(300) + "\u{00e9}";
+        let command = format!("print {}", value);
+        assert!(command.len() > 256);
+        assert!(ctx.eval(&command).is_ok());
+        assert_eq!(&value[..], ctx.editor.status_bar.message().1);
+    }
 
     #[test]
     fn command_tokens() {
@@ -471,4 +1675,169 @@ mod tests {
         assert_eq!(Some("arg]]=]"), tokens.next());
         assert_eq!(None, tokens.next());
     }
+
+    #[test]
+    fn command_args_peek() {
+        let mut args = CommandArgs(CommandTokenizer("arg0 'arg 1'"));
+        assert_eq!(Some("arg0"), args.peek());
+        assert_eq!(Some("arg0"), args.try_next());
+        assert_eq!(Some("arg 1"), args.peek());
+        assert_eq!(Some("arg 1"), args.peek());
+        assert_eq!(Some("arg 1"), args.try_next());
+        assert_eq!(None, args.peek());
+        assert!(args.assert_empty().is_ok());
+    }
+
+    #[test]
+    fn alias_overwrite() {
+        let mut aliases = AliasCollection::default();
+        aliases.add("a", "open");
+        aliases.add("b", "save");
+        assert_eq!(Some("open"), aliases.find("a"));
+
+        aliases.add("a", "close");
+        assert_eq!(Some("close"), aliases.find("a"));
+        aliases.add("a", "quit");
+        assert_eq!(Some("quit"), aliases.find("a"));
+        assert_eq!(Some("save"), aliases.find("b"));
+        assert_eq!(None, aliases.find("c"));
+
+        let mut iter = aliases.iter();
+        assert_eq!(Some(("b", "save")), iter.next());
+        assert_eq!(Some(("a", "quit")), iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn eval_alias() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("alias p print").is_ok());
+        assert!(ctx.eval("p hello").is_ok());
+        assert_eq!("hello", ctx.editor.status_bar.message().1);
+
+        assert!(ctx.eval("alias p quit").is_ok());
+        assert!(matches!(ctx.eval("p"), Ok(EditorControlFlow::Quit)));
+
+        assert!(ctx.eval("alias p ''").is_ok());
+        assert_eq!(None, ctx.editor.commands.aliases.find("p"));
+        assert!(matches!(ctx.eval("p"), Err(CommandError::NoSuchCommand)));
+    }
+
+    #[test]
+    fn eval_alias_placeholders() {
+        let mut ctx = EvalContext::new();
+        assert!(ctx.eval("alias greet 'print hello $1'").is_ok());
+        assert!(ctx.eval("greet world ignored").is_ok());
+        assert_eq!("hello world", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("greet 'big world'").is_ok());
+        assert_eq!("hello big world", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("greet").is_ok());
+        assert_eq!("hello", ctx.editor.status_bar.message().1);
+
+        assert!(ctx.eval("alias swap 'print $2 $1'").is_ok());
+        assert!(ctx.eval("swap a b").is_ok());
+        assert_eq!("b a", ctx.editor.status_bar.message().1);
+
+        assert!(ctx.eval("alias all 'print <$*> $5$'").is_ok());
+        assert!(ctx.eval("all a  b c").is_ok());
+        assert_eq!("<a b c> $", ctx.editor.status_bar.message().1);
+
+        assert!(ctx.eval("alias cost 'print $$1 $$$1 $$*'").is_ok());
+        assert!(ctx.eval("cost 5").is_ok());
+        assert_eq!("$1 $5 $*", ctx.editor.status_bar.message().1);
+
+        assert!(ctx.eval("greet [[it's \"quoted\"]]").is_ok());
+        assert_eq!("hello it's \"quoted\"", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("greet [=['a' \"b\" [[c]]]=]").is_ok());
+        assert_eq!("hello 'a' \"b\" [[c]]", ctx.editor.status_bar.message().1);
+        assert!(ctx.eval("greet [=['a' \"b\" c]]=]").is_ok());
+        assert_eq!("hello 'a' \"b\" c]", ctx.editor.status_bar.message().1);
+    }
+
+    #[test]
+    fn alias_too_long() {
+        let long = "a".repeat(u16::MAX as usize + 1);
+        let mut aliases = AliasCollection::default();
+        assert!(aliases.add("a", "open"));
+        assert!(!aliases.add("b", &long));
+        assert!(!aliases.add(&long, "open"));
+        assert_eq!(None, aliases.find("b"));
+        assert_eq!(Some("open"), aliases.find("a"));
+        assert_eq!(1, aliases.iter().count());
+    }
+
+    #[test]
+    fn command_tokens_with_backslashes() {
+        let mut tokens = CommandTokenizer(r#"open "C:\Users\me\notes.txt" 'a\rb\' C:\x\"#);
+        assert_eq!(Some("open"), tokens.next());
+        assert_eq!(Some(r"C:\Users\me\notes.txt"), tokens.next());
+        assert_eq!(Some(r"a\rb\"), tokens.next());
+        assert_eq!(Some(r"C:\x\"), tokens.next());
+        assert_eq!(None, tokens.next());
+    }
+
+    #[test]
+    fn command_tokens_with_tabs() {
+        let mut tokens = CommandTokenizer("cmd\targ0 \t arg1\t");
+        assert_eq!(Some("cmd"), tokens.next());
+        assert_eq!(Some("arg0"), tokens.next());
+        assert_eq!(Some("arg1"), tokens.next());
+        assert_eq!(None, tokens.next());
+
+        let mut tokens = CommandTokenizer("cmd\t'arg 0'\t\"arg\t1\"");
+        assert_eq!(Some("cmd"), tokens.next());
+        assert_eq!(Some("arg 0"), tokens.next());
+        assert_eq!(Some("arg\t1"), tokens.next());
+        assert_eq!(None, tokens.next());
+
+        let mut tokens = CommandTokenizer("cmd\t'arg0\targ1");
+        assert_eq!(Some("cmd"), tokens.next());
+        assert_eq!(Some("'arg0"), tokens.next());
+        assert_eq!(Some("arg1"), tokens.next());
+        assert_eq!(None, tokens.next());
+
+        let mut tokens = CommandTokenizer("cmd\t[[arg 0]\t]]\t[=[arg1]=]");
+        assert_eq!(Some("cmd"), tokens.next());
+        assert_eq!(Some("arg 0]\t"), tokens.next());
+        assert_eq!(Some("arg1"), tokens.next());
+        assert_eq!(None, tokens.next());
+
+        let mut tokens = CommandTokenizer("cmd\t[[arg0\targ1");
+        assert_eq!(Some("cmd"), tokens.next());
+        assert_eq!(Some("[[arg0"), tokens.next());
+        assert_eq!(Some("arg1"), tokens.next());
+        assert_eq!(None, tokens.next());
+    }
+
+    #[test]
+    fn command_tokens_scan_limit() {
+        let long_arg = "a".repeat(MAX_TOKEN_SCAN_LEN);
+        let too_long =
+            |command: &str| matches!(check_tokens(command), Err(CommandError::TokenTooLong));
+
+        assert!(check_tokens(&format!("cmd [[{}]]", &long_arg[2..])).is_ok());
+        assert!(too_long(&format!("cmd [[{}]] arg", long_arg)));
+        assert!(check_tokens(&format!("cmd '{}'", &long_arg[1..])).is_ok());
+        assert!(too_long(&format!("cmd '{}' arg", long_arg)));
+        assert!(too_long(&format!(
+            "cmd [{}[a]]",
+            "=".repeat(MAX_TOKEN_SCAN_LEN)
+        )));
+        assert!(too_long(&format!(
+            "cmd [[{}",
+            "a ".repeat(MAX_TOKEN_SCAN_LEN)
+        )));
+        assert!(check_tokens(&format!("cmd '{}", long_arg)).is_ok());
+
+        let mut ctx = EvalContext::new();
+        let command = format!("print [=[{}", "a]]".repeat(MAX_TOKEN_SCAN_LEN));
+        assert!(matches!(
+            ctx.eval(&command),
+            Err(CommandError::TokenTooLong)
+        ));
+        assert!(matches!(
+            ctx.editor.commands.check(&command),
+            Err(CommandError::TokenTooLong)
+        ));
+    }
 }
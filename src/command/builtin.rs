@@ -1,17 +1,25 @@
-use std::path::Path;
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
     buffer::{parse_path_and_position, BufferCapabilities, BufferHandle},
     buffer_position::BufferPosition,
     client::ClientManager,
-    command::{BuiltinCommand, CommandContext, CommandError, CompletionSource},
+    command::{
+        BuiltinCommand, CommandContext, CommandError, CommandManager, CommandTokenizer,
+        CompletionSource, MAX_EVAL_DEPTH,
+    },
     config::{ParseConfigError, CONFIG_NAMES},
     cursor::Cursor,
     editor::{Editor, EditorControlFlow},
-    editor_utils::MessageKind,
+    editor_utils::{load_config, parse_process_command, MessageKind},
     glob::InvalidGlobError,
     help, lsp,
-    mode::ModeKind,
+    mode::{Mode, ModeContext, ModeKind},
     navigation_history::NavigationHistory,
     platform::Platform,
     syntax::{Syntax, TokenKind},
@@ -32,11 +40,10 @@ pub static COMMANDS: &[BuiltinCommand] = &[
                 None => (help::main_help_path(), BufferPosition::zero()),
             };
 
-            let handle = ctx.editor.buffer_view_handle_from_path(
-                client_handle,
-                path,
-                BufferCapabilities::log(),
-            );
+            let handle = ctx
+                .editor
+                .buffer_view_handle_from_path(client_handle, path, BufferCapabilities::log())
+                .map_err(CommandError::BufferReadError)?;
 
             let mut cursors = ctx.editor.buffer_views.get_mut(handle).cursors.mut_guard();
             cursors.clear();
@@ -72,6 +79,118 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             Ok(EditorControlFlow::QuitAll)
         },
     },
+    BuiltinCommand {
+        name: "print",
+        completions: &[],
+        func: |ctx| {
+            let mut write = ctx.editor.status_bar.write(MessageKind::Info);
+            if let Some(value) = ctx.args.try_next() {
+                write.str(value);
+            }
+            while let Some(value) = ctx.args.try_next() {
+                write.str(" ");
+                write.str(value);
+            }
+            Ok(EditorControlFlow::Continue)
+        },
+    },
+    BuiltinCommand {
+        name: "eval",
+        completions: &[CompletionSource::Commands],
+        func: |ctx| {
+            let command = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+            CommandManager::try_eval_nested(
+                ctx.editor,
+                ctx.platform,
+                ctx.clients,
+                ctx.client_handle,
+                command,
+            )
+        },
+    },
+    BuiltinCommand {
+        name: "repeat-last-command",
+        completions: &[],
+        func: |ctx| {
+            ctx.args.assert_empty()?;
+
+            let commands = &ctx.editor.commands;
+            let entry = (0..commands.history_len())
+                .rev()
+                .map(|i| commands.history_entry(i))
+                .find(|entry| match CommandTokenizer(entry).next() {
+                    Some(name) => name.trim_end_matches('!') != "repeat-last-command",
+                    None => false,
+                });
+            let command = match entry {
+                Some(entry) => ctx.editor.string_pool.acquire_with(entry),
+                None => return Err(CommandError::NoCommandToRepeat),
+            };
+
+            let result = CommandManager::try_eval_nested(
+                ctx.editor,
+                ctx.platform,
+                ctx.clients,
+                ctx.client_handle,
+                &command,
+            );
+            ctx.editor.string_pool.release(command);
+            result
+        },
+    },
+    BuiltinCommand {
+        name: "insert-output",
+        completions: &[CompletionSource::Commands],
+        func: |ctx| {
+            let command = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+
+            let buffer_view_handle = ctx.current_buffer_view_handle()?;
+            ctx.editor.status_bar.clear();
+            let flow = CommandManager::try_eval_nested(
+                ctx.editor,
+                ctx.platform,
+                ctx.clients,
+                ctx.client_handle,
+                command,
+            )?;
+
+            let (kind, output) = ctx.editor.status_bar.message();
+            if kind == MessageKind::Info && !output.is_empty() {
+                let output = ctx.editor.string_pool.acquire_with(output);
+                ctx.editor.status_bar.clear();
+                ctx.editor
+                    .buffer_views
+                    .get(buffer_view_handle)
+                    .insert_text_at_cursor_positions(
+                        &mut ctx.editor.buffers,
+                        &mut ctx.editor.word_database,
+                        &output,
+                        &mut ctx.editor.events,
+                    );
+                ctx.editor.string_pool.release(output);
+            }
+
+            Ok(flow)
+        },
+    },
+    BuiltinCommand {
+        name: "now",
+        completions: &[],
+        func: |ctx| {
+            ctx.args.assert_empty()?;
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            ctx.editor
+                .status_bar
+                .write(MessageKind::Info)
+                .fmt(format_args!("{}", now));
+            Ok(EditorControlFlow::Continue)
+        },
+    },
     BuiltinCommand {
         name: "open",
         completions: &[CompletionSource::Files],
@@ -81,19 +200,18 @@ pub static COMMANDS: &[BuiltinCommand] = &[
 
             let client_handle = ctx.client_handle()?;
             let (path, position) = parse_path_and_position(path);
+            let resolved_path = ctx.resolve_path(path);
+            let path = resolved_path.as_deref().unwrap_or_else(|| Path::new(path));
 
             NavigationHistory::save_client_snapshot(
                 ctx.clients.get_mut(client_handle),
                 &ctx.editor.buffer_views,
             );
 
-            let path = ctx.editor.string_pool.acquire_with(path);
-            let handle = ctx.editor.buffer_view_handle_from_path(
-                client_handle,
-                Path::new(&path),
-                BufferCapabilities::text(),
-            );
-            ctx.editor.string_pool.release(path);
+            let handle = ctx
+                .editor
+                .buffer_view_handle_from_path(client_handle, path, BufferCapabilities::text())
+                .map_err(CommandError::BufferReadError)?;
 
             if let Some(position) = position {
                 let mut cursors = ctx.editor.buffer_views.get_mut(handle).cursors.mut_guard();
@@ -115,12 +233,29 @@ pub static COMMANDS: &[BuiltinCommand] = &[
         name: "save",
         completions: &[],
         func: |ctx| {
-            let path = ctx.args.try_next().map(|p| Path::new(p));
+            let path = ctx.args.try_next();
             ctx.args.assert_empty()?;
+            let resolved_path = path.and_then(|p| ctx.resolve_path(p));
+            let path = resolved_path.as_deref().or_else(|| path.map(Path::new));
 
             let buffer_handle = ctx.current_buffer_handle()?;
             let buffer = ctx.editor.buffers.get_mut(buffer_handle);
+            if path.is_none()
+                && !ctx.bang
+                && buffer.capabilities.can_save
+                && buffer.changed_on_disk()
+            {
+                return Err(CommandError::FileChangedOnDisk);
+            }
 
+            let will_save = path.is_some() || buffer.capabilities.can_save;
+            if will_save && ctx.editor.config.trim_trailing_blank_lines {
+                buffer.trim_trailing_blank_lines(
+                    &mut ctx.editor.word_database,
+                    &mut ctx.editor.events,
+                );
+                buffer.commit_edits();
+            }
             buffer
                 .save_to_file(path, &mut ctx.editor.events)
                 .map_err(CommandError::IoError)?;
@@ -128,7 +263,23 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             ctx.editor
                 .status_bar
                 .write(MessageKind::Info)
-                .fmt(format_args!("buffer saved to {:?}", &buffer.path));
+                .fmt(format_args!(
+                    "{} lines written to {:?}",
+                    buffer.content().line_count(),
+                    &buffer.path
+                ));
+            Ok(EditorControlFlow::Continue)
+        },
+    },
+    BuiltinCommand {
+        name: "trim-whitespace",
+        completions: &[],
+        func: |ctx| {
+            ctx.args.assert_empty()?;
+            let buffer_handle = ctx.current_buffer_handle()?;
+            let buffer = ctx.editor.buffers.get_mut(buffer_handle);
+            buffer.trim_trailing_whitespace(&mut ctx.editor.word_database, &mut ctx.editor.events);
+            buffer.commit_edits();
             Ok(EditorControlFlow::Continue)
         },
     },
@@ -139,15 +290,38 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             ctx.args.assert_empty()?;
 
             let mut count = 0;
+            let mut errors = String::new();
             for buffer in ctx.editor.buffers.iter_mut() {
                 if buffer.capabilities.can_save {
-                    buffer
-                        .save_to_file(None, &mut ctx.editor.events)
-                        .map_err(CommandError::IoError)?;
-                    count += 1;
+                    if !ctx.bang && buffer.changed_on_disk() {
+                        let _ = write!(
+                            errors,
+                            "\n{:?}: {}",
+                            &buffer.path,
+                            CommandError::FileChangedOnDisk
+                        );
+                        continue;
+                    }
+                    if ctx.editor.config.trim_trailing_blank_lines {
+                        buffer.trim_trailing_blank_lines(
+                            &mut ctx.editor.word_database,
+                            &mut ctx.editor.events,
+                        );
+                        buffer.commit_edits();
+                    }
+                    match buffer.save_to_file(None, &mut ctx.editor.events) {
+                        Ok(()) => count += 1,
+                        Err(error) => {
+                            let _ = write!(errors, "\n{:?}: {}", &buffer.path, error);
+                        }
+                    }
                 }
             }
 
+            if !errors.is_empty() {
+                return Err(CommandError::BuffersNotSaved(errors));
+            }
+
             ctx.editor
                 .status_bar
                 .write(MessageKind::Info)
@@ -156,7 +330,7 @@ pub static COMMANDS: &[BuiltinCommand] = &[
         },
     },
     BuiltinCommand {
-        name: "reopen",
+        name: "reload",
         completions: &[],
         func: |ctx| {
             ctx.args.assert_empty()?;
@@ -166,18 +340,22 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             let buffer = ctx.editor.buffers.get_mut(buffer_handle);
 
             buffer
-                .discard_and_reload_from_file(&mut ctx.editor.word_database, &mut ctx.editor.events)
-                .map_err(CommandError::IoError)?;
+                .discard_and_reload_from_file(
+                    &mut ctx.editor.word_database,
+                    &mut ctx.editor.events,
+                    ctx.editor.config.max_open_size,
+                )
+                .map_err(CommandError::BufferReadError)?;
 
             ctx.editor
                 .status_bar
                 .write(MessageKind::Info)
-                .str("buffer reopened");
+                .str("buffer reloaded");
             Ok(EditorControlFlow::Continue)
         },
     },
     BuiltinCommand {
-        name: "reopen-all",
+        name: "reload-all",
         completions: &[],
         func: |ctx| {
             ctx.args.assert_empty()?;
@@ -189,15 +367,16 @@ pub static COMMANDS: &[BuiltinCommand] = &[
                     .discard_and_reload_from_file(
                         &mut ctx.editor.word_database,
                         &mut ctx.editor.events,
+                        ctx.editor.config.max_open_size,
                     )
-                    .map_err(CommandError::IoError)?;
+                    .map_err(CommandError::BufferReadError)?;
                 count += 1;
             }
 
             ctx.editor
                 .status_bar
                 .write(MessageKind::Info)
-                .fmt(format_args!("{} buffers reopened", count));
+                .fmt(format_args!("{} buffers reloaded", count));
             Ok(EditorControlFlow::Continue)
         },
     },
@@ -243,6 +422,135 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             Ok(EditorControlFlow::Continue)
         },
     },
+    BuiltinCommand {
+        name: "spawn-into",
+        completions: &[CompletionSource::Buffers],
+        func: |ctx| {
+            let path = ctx.args.next()?;
+            let command = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+
+            let command =
+                parse_process_command(command).map_err(CommandError::ProcessCommandError)?;
+            let client_handle = ctx.client_handle()?;
+
+            NavigationHistory::save_client_snapshot(
+                ctx.clients.get_mut(client_handle),
+                &ctx.editor.buffer_views,
+            );
+
+            let buffer_view_handle = ctx
+                .editor
+                .buffer_view_handle_from_path(
+                    client_handle,
+                    Path::new(path),
+                    BufferCapabilities::log(),
+                )
+                .map_err(CommandError::BufferReadError)?;
+            let buffer_handle = ctx
+                .editor
+                .buffer_views
+                .get(buffer_view_handle)
+                .buffer_handle;
+            let position = ctx.editor.buffers.get(buffer_handle).content().end();
+            ctx.editor.buffers.spawn_insert_process(
+                ctx.platform,
+                command,
+                buffer_handle,
+                position,
+                None,
+            );
+
+            ctx.clients
+                .get_mut(client_handle)
+                .set_buffer_view_handle(Some(buffer_view_handle), &mut ctx.editor.events);
+
+            Ok(EditorControlFlow::Continue)
+        },
+    },
+    BuiltinCommand {
+        name: "source",
+        completions: &[CompletionSource::Files],
+        func: |ctx| {
+            let path = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+
+            let path = ctx
+                .resolve_path(path)
+                .unwrap_or_else(|| PathBuf::from(path));
+            if ctx.bang && !path.exists() {
+                return Ok(EditorControlFlow::Continue);
+            }
+            let flow = source(ctx, &path)?;
+            Ok(flow.unwrap_or(EditorControlFlow::Continue))
+        },
+    },
+    BuiltinCommand {
+        name: "source-dir",
+        completions: &[CompletionSource::Files],
+        func: |ctx| {
+            let path = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+
+            let path = ctx
+                .resolve_path(path)
+                .unwrap_or_else(|| PathBuf::from(path));
+            let mut paths = Vec::new();
+            for entry in fs::read_dir(path).map_err(CommandError::IoError)? {
+                let path = entry.map_err(CommandError::IoError)?.path();
+                if path.is_file() && path.extension().map(|e| e == "pp").unwrap_or(false) {
+                    paths.push(path);
+                }
+            }
+            paths.sort();
+
+            for path in &paths {
+                match source(ctx, path)? {
+                    Some(EditorControlFlow::Continue) => (),
+                    Some(flow) => return Ok(flow),
+                    None => break,
+                }
+            }
+            Ok(EditorControlFlow::Continue)
+        },
+    },
+    BuiltinCommand {
+        name: "check-config",
+        completions: &[CompletionSource::Files],
+        func: |ctx| {
+            let path = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+
+            let path = ctx
+                .resolve_path(path)
+                .unwrap_or_else(|| PathBuf::from(path));
+            let content = fs::read_to_string(&path).map_err(CommandError::IoError)?;
+            let name = path.to_string_lossy();
+
+            let mut aliases = ctx.editor.commands.aliases.clone();
+            for (line_index, line) in content.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                if let Err(error) = ctx.editor.commands.check_with_aliases(line, &mut aliases) {
+                    return Err(CommandError::InvalidConfigLine(format!(
+                        "{}:{}\n{}\n{}",
+                        name,
+                        line_index + 1,
+                        line,
+                        error
+                    )));
+                }
+            }
+
+            ctx.editor
+                .status_bar
+                .write(MessageKind::Info)
+                .fmt(format_args!("no errors found in {}", name));
+            Ok(EditorControlFlow::Continue)
+        },
+    },
     BuiltinCommand {
         name: "status",
         completions: &[],
@@ -258,6 +566,72 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             Ok(EditorControlFlow::Continue)
         },
     },
+    BuiltinCommand {
+        name: "clients",
+        completions: &[],
+        func: |ctx| {
+            ctx.args.assert_empty()?;
+
+            let mut output = ctx.editor.string_pool.acquire();
+            for client in ctx.clients.iter() {
+                if !output.is_empty() {
+                    output.push('\n');
+                }
+                let handle = client.handle();
+                let current = if Some(handle) == ctx.client_handle {
+                    "*"
+                } else {
+                    ""
+                };
+                let path = client
+                    .buffer_view_handle()
+                    .map(|h| ctx.editor.buffer_views.get(h).buffer_handle)
+                    .map(|h| ctx.editor.buffers.get(h).path.to_str().unwrap_or(""));
+                let _ = write!(
+                    output,
+                    "{}{}: {}",
+                    handle.into_index(),
+                    current,
+                    path.unwrap_or("no buffer")
+                );
+            }
+            if output.is_empty() {
+                output.push_str("no clients");
+            }
+
+            ctx.editor.status_bar.write(MessageKind::Info).str(&output);
+            ctx.editor.string_pool.release(output);
+            Ok(EditorControlFlow::Continue)
+        },
+    },
+    BuiltinCommand {
+        name: "buffer-path",
+        completions: &[],
+        func: |ctx| {
+            ctx.args.assert_empty()?;
+            let buffer_handle = ctx.current_buffer_handle()?;
+            let path = &ctx.editor.buffers.get(buffer_handle).path;
+            ctx.editor
+                .status_bar
+                .write(MessageKind::Info)
+                .str(path.to_str().unwrap_or(""));
+            Ok(EditorControlFlow::Continue)
+        },
+    },
+    BuiltinCommand {
+        name: "buffer-modified",
+        completions: &[],
+        func: |ctx| {
+            ctx.args.assert_empty()?;
+            let buffer_handle = ctx.current_buffer_handle()?;
+            let needs_save = ctx.editor.buffers.get(buffer_handle).needs_save();
+            ctx.editor
+                .status_bar
+                .write(MessageKind::Info)
+                .str(if needs_save { "true" } else { "" });
+            Ok(EditorControlFlow::Continue)
+        },
+    },
     BuiltinCommand {
         name: "config",
         completions: &[(CompletionSource::Custom(CONFIG_NAMES))],
@@ -300,17 +674,87 @@ pub static COMMANDS: &[BuiltinCommand] = &[
 
             match value {
                 Some(value) => {
-                    let encoded =
-                        u32::from_str_radix(value, 16).map_err(|_| CommandError::NoSuchColor)?;
+                    let hex = value.strip_prefix("0x").unwrap_or(value);
+                    if hex.is_empty()
+                        || hex.len() > 6
+                        || !hex.bytes().all(|b| b.is_ascii_hexdigit())
+                    {
+                        return Err(CommandError::InvalidColorValue);
+                    }
+                    let encoded = u32::from_str_radix(hex, 16)
+                        .map_err(|_| CommandError::InvalidColorValue)?;
                     *color = Color::from_u32(encoded);
                 }
                 None => ctx
                     .editor
                     .status_bar
                     .write(MessageKind::Info)
-                    .fmt(format_args!("0x{:0<6x}", color.into_u32())),
+                    .fmt(format_args!("0x{:06x}", color.into_u32())),
+            }
+
+            Ok(EditorControlFlow::Continue)
+        },
+    },
+    BuiltinCommand {
+        name: "execute-keys",
+        completions: &[],
+        func: |ctx| {
+            let keys = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+
+            let client_handle = ctx.client_handle()?;
+            if ctx.editor.commands.eval_depth >= MAX_EVAL_DEPTH {
+                return Err(CommandError::EvalTooDeep);
+            }
+            let keys = match ctx.editor.buffered_keys.parse(keys) {
+                Ok(keys) => keys,
+                Err(error) => return Err(CommandError::KeyParseError(error.error)),
+            };
+
+            let mut mode_ctx = ModeContext {
+                editor: ctx.editor,
+                platform: ctx.platform,
+                clients: ctx.clients,
+                client_handle,
+            };
+            Mode::change_to(&mut mode_ctx, ModeKind::default());
+
+            ctx.editor.commands.eval_depth += 1;
+            let flow = ctx
+                .editor
+                .execute_keys(ctx.platform, ctx.clients, client_handle, keys);
+            ctx.editor.commands.eval_depth -= 1;
+            Ok(flow)
+        },
+    },
+    BuiltinCommand {
+        name: "insert-mode",
+        completions: &[],
+        func: |ctx| {
+            let text = ctx.args.try_next();
+            ctx.args.assert_empty()?;
+
+            let client_handle = ctx.client_handle()?;
+            let handle = ctx.current_buffer_view_handle()?;
+            if let Some(text) = text {
+                ctx.editor
+                    .buffer_views
+                    .get(handle)
+                    .insert_text_at_cursor_positions(
+                        &mut ctx.editor.buffers,
+                        &mut ctx.editor.word_database,
+                        text,
+                        &mut ctx.editor.events,
+                    );
             }
 
+            let mut mode_ctx = ModeContext {
+                editor: ctx.editor,
+                platform: ctx.platform,
+                clients: ctx.clients,
+                client_handle,
+            };
+            Mode::change_to(&mut mode_ctx, ModeKind::Insert);
             Ok(EditorControlFlow::Continue)
         },
     },
@@ -346,8 +790,14 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             let from = ctx.args.next()?;
             let to = ctx.args.next()?;
             ctx.args.assert_empty()?;
-            ctx.editor.commands.aliases.add(from, to);
-            Ok(EditorControlFlow::Continue)
+            if to.is_empty() {
+                ctx.editor.commands.aliases.remove(from);
+                Ok(EditorControlFlow::Continue)
+            } else if ctx.editor.commands.aliases.add(from, to) {
+                Ok(EditorControlFlow::Continue)
+            } else {
+                Err(CommandError::AliasTooLong)
+            }
         },
     },
     BuiltinCommand {
@@ -383,6 +833,25 @@ pub static COMMANDS: &[BuiltinCommand] = &[
             }
         },
     },
+    BuiltinCommand {
+        name: "buffer-set-syntax",
+        completions: &[],
+        func: |ctx| {
+            let path = ctx.args.next()?;
+            ctx.args.assert_empty()?;
+
+            let buffer_handle = ctx.current_buffer_handle()?;
+            let syntax_handle = match ctx.editor.syntaxes.find_handle_by_path(path) {
+                Some(handle) => handle,
+                None => return Err(CommandError::NoSuchSyntax),
+            };
+            ctx.editor
+                .buffers
+                .get_mut(buffer_handle)
+                .set_syntax(syntax_handle);
+            Ok(EditorControlFlow::Continue)
+        },
+    },
     BuiltinCommand {
         name: "syntax-keywords",
         completions: &[],
@@ -446,11 +915,13 @@ pub static COMMANDS: &[BuiltinCommand] = &[
                 buffer_handle,
                 |editor, _, clients, client| match client.log_file_path() {
                     Some(path) => {
-                        let buffer_view_handle = editor.buffer_view_handle_from_path(
-                            client_handle,
-                            Path::new(path),
-                            BufferCapabilities::log(),
-                        );
+                        let buffer_view_handle = editor
+                            .buffer_view_handle_from_path(
+                                client_handle,
+                                Path::new(path),
+                                BufferCapabilities::log(),
+                            )
+                            .map_err(CommandError::BufferReadError)?;
                         let client = clients.get_mut(client_handle);
                         NavigationHistory::save_client_snapshot(client, &editor.buffer_views);
                         client.set_buffer_view_handle(Some(buffer_view_handle), &mut editor.events);
@@ -628,6 +1099,23 @@ pub static COMMANDS: &[BuiltinCommand] = &[
     },
 ];
 
+fn source(
+    ctx: &mut CommandContext,
+    path: &Path,
+) -> Result<Option<EditorControlFlow>, CommandError> {
+    let content = fs::read_to_string(path).map_err(CommandError::IoError)?;
+    let name = path.to_string_lossy();
+
+    let commands = &mut ctx.editor.commands;
+    if commands.eval_depth >= MAX_EVAL_DEPTH {
+        return Err(CommandError::EvalTooDeep);
+    }
+    commands.eval_depth += 1;
+    let flow = load_config(ctx.editor, ctx.platform, ctx.clients, &name, &content);
+    ctx.editor.commands.eval_depth -= 1;
+    Ok(flow)
+}
+
 fn map(ctx: &mut CommandContext, mode: ModeKind) -> Result<EditorControlFlow, CommandError> {
     let from = ctx.args.next()?;
     let to = ctx.args.next()?;
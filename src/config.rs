@@ -1,4 +1,4 @@
-use std::{fmt, num::NonZeroU8};
+use std::{fmt, num::NonZeroU8, str::FromStr};
 
 pub enum ParseConfigError {
     NoSuchConfig,
@@ -13,6 +13,33 @@ impl fmt::Display for ParseConfigError {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CaseMatching {
+    Insensitive,
+    Smart,
+    Sensitive,
+}
+impl FromStr for CaseMatching {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "insensitive" => Ok(Self::Insensitive),
+            "smart" => Ok(Self::Smart),
+            "sensitive" => Ok(Self::Sensitive),
+            _ => Err(()),
+        }
+    }
+}
+impl fmt::Display for CaseMatching {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Insensitive => f.write_str("insensitive"),
+            Self::Smart => f.write_str("smart"),
+            Self::Sensitive => f.write_str("sensitive"),
+        }
+    }
+}
+
 macro_rules! config_values {
     ($($name:ident: $type:ty = $default:expr,)*) => {
         pub static CONFIG_NAMES: &[&str] = &[$(stringify!($name),)*];
@@ -68,6 +95,8 @@ macro_rules! config_values {
 config_values! {
     tab_size: NonZeroU8 = NonZeroU8::new(4).unwrap(),
     indent_with_tabs: bool = true,
+    indent_size: NonZeroU8 = NonZeroU8::new(4).unwrap(),
+    auto_indent: bool = true,
 
     visual_empty: u8 = b'~',
     visual_space: u8 = b'.',
@@ -75,5 +104,11 @@ config_values! {
     visual_tab_repeat: u8 = b' ',
 
     completion_min_len: u8 = 3,
+    case_matching: CaseMatching = CaseMatching::Smart,
     picker_max_height: u8 = 8,
+    max_cursors: NonZeroU8 = NonZeroU8::new(u8::MAX).unwrap(),
+
+    max_open_size: u64 = 64 * 1024 * 1024,
+    autosave: bool = false,
+    trim_trailing_blank_lines: bool = false,
 }
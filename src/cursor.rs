@@ -5,7 +5,7 @@ use std::{
 
 use crate::{
     buffer::{BufferContent, CharDisplayDistances},
-    buffer_position::{BufferPosition, BufferRange},
+    buffer_position::{BufferPosition, BufferPositionIndex, BufferRange},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,6 +44,8 @@ pub struct CursorCollection {
     saved_display_distances: Box<[u32; Self::capacity()]>,
     saved_display_distances_len: u8,
     main_cursor_index: u8,
+    max_len: u8,
+    dropped_cursors: bool,
 }
 
 impl CursorCollection {
@@ -58,9 +60,19 @@ impl CursorCollection {
             saved_display_distances: Box::new([0; Self::capacity()]),
             saved_display_distances_len: 0,
             main_cursor_index: 0,
+            max_len: Self::capacity() as _,
+            dropped_cursors: false,
         }
     }
 
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len.clamp(1, Self::capacity()) as _;
+    }
+
+    pub fn take_dropped_cursors(&mut self) -> bool {
+        std::mem::replace(&mut self.dropped_cursors, false)
+    }
+
     pub fn main_cursor_index(&self) -> usize {
         self.main_cursor_index as _
     }
@@ -69,6 +81,10 @@ impl CursorCollection {
         &self.cursors[self.main_cursor_index as usize]
     }
 
+    pub fn ranges<'a>(&'a self) -> impl 'a + ExactSizeIterator<Item = BufferRange> {
+        self[..].iter().map(Cursor::to_range)
+    }
+
     pub fn mut_guard(&mut self) -> CursorCollectionMutGuard {
         CursorCollectionMutGuard {
             inner: self,
@@ -149,10 +165,43 @@ impl<'a> CursorCollectionMutGuard<'a> {
     }
 
     pub fn add(&mut self, cursor: Cursor) {
-        if let Some(len) = self.inner.len.checked_add(1) {
+        if self.inner.len < self.inner.max_len {
             self.inner.cursors[self.inner.len as usize] = cursor;
             self.inner.main_cursor_index = self.inner.len;
-            self.inner.len = len;
+            self.inner.len += 1;
+        } else {
+            self.inner.dropped_cursors = true;
+        }
+    }
+
+    pub fn add_block<F>(
+        &mut self,
+        top_line_index: BufferPositionIndex,
+        bottom_line_index: BufferPositionIndex,
+        from_column_byte_index: BufferPositionIndex,
+        to_column_byte_index: BufferPositionIndex,
+        line_len: F,
+    ) where
+        F: Fn(BufferPositionIndex) -> BufferPositionIndex,
+    {
+        for line_index in top_line_index..=bottom_line_index {
+            let len = line_len(line_index);
+            self.add(Cursor {
+                anchor: BufferPosition::line_col(line_index, from_column_byte_index.min(len)),
+                position: BufferPosition::line_col(line_index, to_column_byte_index.min(len)),
+            });
+        }
+    }
+
+    pub fn collapse_anchors(&mut self) {
+        for c in &mut self.inner.cursors[..self.inner.len as usize] {
+            c.anchor = c.position;
+        }
+    }
+
+    pub fn collapse_to_anchors(&mut self) {
+        for c in &mut self.inner.cursors[..self.inner.len as usize] {
+            c.position = c.anchor;
         }
     }
 
@@ -246,6 +295,129 @@ impl<'a> Drop for CursorCollectionMutGuard<'a> {
 mod tests {
     use super::*;
 
+    fn cursor(
+        anchor: (BufferPositionIndex, BufferPositionIndex),
+        position: (BufferPositionIndex, BufferPositionIndex),
+    ) -> Cursor {
+        Cursor {
+            anchor: BufferPosition::line_col(anchor.0, anchor.1),
+            position: BufferPosition::line_col(position.0, position.1),
+        }
+    }
+
+    fn range(
+        from: (BufferPositionIndex, BufferPositionIndex),
+        to: (BufferPositionIndex, BufferPositionIndex),
+    ) -> BufferRange {
+        BufferRange::between(
+            BufferPosition::line_col(from.0, from.1),
+            BufferPosition::line_col(to.0, to.1),
+        )
+    }
+
+    #[test]
+    fn cursor_insert() {
+        let mut c = cursor((1, 2), (1, 5));
+        c.insert(range((1, 2), (1, 4)));
+        assert_eq!(cursor((1, 4), (1, 7)), c);
+
+        let mut c = cursor((1, 5), (1, 2));
+        c.insert(range((1, 3), (3, 1)));
+        assert_eq!(cursor((3, 3), (1, 2)), c);
+
+        let mut c = cursor((1, 2), (2, 0));
+        c.insert(range((0, 4), (2, 0)));
+        assert_eq!(cursor((3, 2), (4, 0)), c);
+
+        let mut c = cursor((1, 2), (1, 5));
+        c.insert(range((1, 6), (2, 0)));
+        assert_eq!(cursor((1, 2), (1, 5)), c);
+    }
+
+    #[test]
+    fn cursor_delete() {
+        let mut c = cursor((1, 2), (1, 6));
+        c.delete(range((1, 0), (1, 4)));
+        assert_eq!(cursor((1, 0), (1, 2)), c);
+
+        let mut c = cursor((1, 6), (1, 2));
+        c.delete(range((1, 0), (1, 4)));
+        assert_eq!(cursor((1, 2), (1, 0)), c);
+
+        let mut c = cursor((2, 1), (3, 5));
+        c.delete(range((0, 3), (3, 2)));
+        assert_eq!(cursor((0, 3), (0, 6)), c);
+
+        let mut c = cursor((4, 1), (5, 2));
+        c.delete(range((1, 3), (3, 0)));
+        assert_eq!(cursor((2, 1), (3, 2)), c);
+
+        let mut c = cursor((1, 2), (1, 4));
+        c.delete(range((1, 0), (2, 0)));
+        assert_eq!(cursor((1, 0), (1, 0)), c);
+
+        let mut c = cursor((1, 2), (1, 4));
+        c.delete(range((1, 4), (1, 8)));
+        assert_eq!(cursor((1, 2), (1, 4)), c);
+    }
+
+    #[test]
+    fn add_stops_at_max_len() {
+        let mut cursors = CursorCollection::new();
+        cursors.set_max_len(2);
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut.add(cursor((1, 0), (1, 0)));
+        cursors_mut.add(cursor((2, 0), (2, 0)));
+        drop(cursors_mut);
+        assert_eq!(2, cursors[..].len());
+        assert!(cursors.take_dropped_cursors());
+        assert!(!cursors.take_dropped_cursors());
+        assert_eq!(BufferPosition::line_col(1, 0), cursors.main_cursor().position);
+    }
+
+    #[test]
+    fn collapse_cursors() {
+        let reversed = Cursor {
+            anchor: BufferPosition::line_col(1, 4),
+            position: BufferPosition::line_col(1, 1),
+        };
+        let forward = Cursor {
+            anchor: BufferPosition::line_col(2, 0),
+            position: BufferPosition::line_col(2, 3),
+        };
+
+        let mut cursors = CursorCollection::new();
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut.clear();
+        cursors_mut.add(reversed);
+        cursors_mut.add(forward);
+        cursors_mut.collapse_to_anchors();
+        drop(cursors_mut);
+        let mut iter = cursors[..].iter();
+        let cursor = iter.next().unwrap();
+        assert_eq!(BufferPosition::line_col(1, 4), cursor.anchor);
+        assert_eq!(BufferPosition::line_col(1, 4), cursor.position);
+        let cursor = iter.next().unwrap();
+        assert_eq!(BufferPosition::line_col(2, 0), cursor.anchor);
+        assert_eq!(BufferPosition::line_col(2, 0), cursor.position);
+        assert!(iter.next().is_none());
+
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut.clear();
+        cursors_mut.add(reversed);
+        cursors_mut.add(forward);
+        cursors_mut.collapse_anchors();
+        drop(cursors_mut);
+        let mut iter = cursors[..].iter();
+        let cursor = iter.next().unwrap();
+        assert_eq!(BufferPosition::line_col(1, 1), cursor.anchor);
+        assert_eq!(BufferPosition::line_col(1, 1), cursor.position);
+        let cursor = iter.next().unwrap();
+        assert_eq!(BufferPosition::line_col(2, 3), cursor.anchor);
+        assert_eq!(BufferPosition::line_col(2, 3), cursor.position);
+        assert!(iter.next().is_none());
+    }
+
     #[test]
     fn merge_cursor() {
         let mut cursors = CursorCollection::new();
@@ -320,6 +492,85 @@ mod tests {
         assert!(cursors.next().is_none());
     }
 
+    #[test]
+    fn add_block_cursors() {
+        let line_lens = [5, 1, 8];
+        let mut cursors = CursorCollection::new();
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut.clear();
+        cursors_mut.add_block(0, 2, 2, 4, |i| line_lens[i as usize]);
+        drop(cursors_mut);
+
+        assert_eq!(3, cursors[..].len());
+        assert_eq!(2, cursors.main_cursor_index());
+        let mut ranges = cursors.ranges();
+        assert_eq!(
+            Some(BufferRange::between(
+                BufferPosition::line_col(0, 2),
+                BufferPosition::line_col(0, 4)
+            )),
+            ranges.next()
+        );
+        assert_eq!(
+            Some(BufferRange::between(
+                BufferPosition::line_col(1, 1),
+                BufferPosition::line_col(1, 1)
+            )),
+            ranges.next()
+        );
+        assert_eq!(
+            Some(BufferRange::between(
+                BufferPosition::line_col(2, 2),
+                BufferPosition::line_col(2, 4)
+            )),
+            ranges.next()
+        );
+        assert_eq!(None, ranges.next());
+        drop(ranges);
+
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut.add_block(2, 2, 4, 3, |i| line_lens[i as usize]);
+        drop(cursors_mut);
+        assert_eq!(3, cursors[..].len());
+        assert_eq!(BufferPosition::line_col(2, 2), cursors[2].anchor);
+        assert_eq!(BufferPosition::line_col(2, 4), cursors[2].position);
+    }
+
+    #[test]
+    fn merge_zero_width_cursors() {
+        let position = BufferPosition::line_col(2, 2);
+        let zero_width = Cursor {
+            anchor: position,
+            position,
+        };
+
+        let mut cursors = CursorCollection::new();
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut.clear();
+        cursors_mut.add(Cursor {
+            anchor: BufferPosition::line_col(5, 0),
+            position: BufferPosition::line_col(5, 0),
+        });
+        cursors_mut.add(zero_width);
+        cursors_mut.add(zero_width);
+        cursors_mut.add(zero_width);
+        drop(cursors_mut);
+
+        assert_eq!(2, cursors[..].len());
+        assert_eq!(zero_width, cursors[0]);
+        assert_eq!(zero_width, *cursors.main_cursor());
+        assert_eq!(BufferPosition::line_col(5, 0), cursors[1].position);
+
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut.set_main_cursor_index(1);
+        cursors_mut[1] = zero_width;
+        drop(cursors_mut);
+
+        assert_eq!(1, cursors[..].len());
+        assert_eq!(0, cursors.main_cursor_index());
+        assert_eq!(zero_width, *cursors.main_cursor());
+    }
+
     #[test]
     fn no_merge_cursor() {
         let mut cursors = CursorCollection::new();
@@ -359,6 +610,36 @@ mod tests {
         assert!(cursors.next().is_none());
     }
 
+    #[test]
+    fn cursor_ranges() {
+        let mut cursors = CursorCollection::new();
+        let mut cursors_mut = cursors.mut_guard();
+        cursors_mut[0].anchor = BufferPosition::line_col(4, 0);
+        cursors_mut[0].position = BufferPosition::line_col(3, 2);
+        cursors_mut.add(Cursor {
+            anchor: BufferPosition::line_col(0, 1),
+            position: BufferPosition::line_col(0, 3),
+        });
+        cursors_mut.add(Cursor {
+            anchor: BufferPosition::line_col(2, 0),
+            position: BufferPosition::line_col(1, 0),
+        });
+        drop(cursors_mut);
+
+        let mut ranges = cursors.ranges();
+        assert_eq!(3, ranges.len());
+        let range = ranges.next().unwrap();
+        assert_eq!(BufferPosition::line_col(0, 1), range.from);
+        assert_eq!(BufferPosition::line_col(0, 3), range.to);
+        let range = ranges.next().unwrap();
+        assert_eq!(BufferPosition::line_col(1, 0), range.from);
+        assert_eq!(BufferPosition::line_col(2, 0), range.to);
+        let range = ranges.next().unwrap();
+        assert_eq!(BufferPosition::line_col(3, 2), range.from);
+        assert_eq!(BufferPosition::line_col(4, 0), range.to);
+        assert!(ranges.next().is_none());
+    }
+
     #[test]
     fn move_and_merge_cursors() {
         let mut cursors = CursorCollection::new();
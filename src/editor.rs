@@ -4,12 +4,12 @@ use std::{
 };
 
 use crate::{
-    buffer::{BufferCapabilities, BufferCollection},
+    buffer::{BufferCapabilities, BufferCollection, BufferReadError},
     buffer_view::{BufferViewCollection, BufferViewHandle},
     client::{Client, ClientHandle, ClientManager},
     command::CommandManager,
     config::Config,
-    editor_utils::{ReadLine, StatusBar, StringPool},
+    editor_utils::{MessageKind, ReadLine, StatusBar, StringPool},
     events::{
         ClientEvent, EditorEvent, EditorEventIter, EditorEventQueue, KeyParseAllError, KeyParser,
         TargetClient,
@@ -144,19 +144,30 @@ impl Editor {
         client_handle: ClientHandle,
         path: &Path,
         capabilities: BufferCapabilities,
-    ) -> BufferViewHandle {
+    ) -> Result<BufferViewHandle, BufferReadError> {
         if let Some(buffer_handle) = self.buffers.find_with_path(&self.current_directory, path) {
-            self.buffer_views
-                .buffer_view_handle_from_buffer_handle(client_handle, buffer_handle)
+            Ok(self
+                .buffer_views
+                .buffer_view_handle_from_buffer_handle(client_handle, buffer_handle))
         } else {
             let path = path.strip_prefix(&self.current_directory).unwrap_or(path);
             let buffer = self.buffers.add_new();
             buffer.path.clear();
             buffer.path.push(path);
             buffer.capabilities = capabilities;
-            let _ = buffer.discard_and_reload_from_file(&mut self.word_database, &mut self.events);
 
-            self.buffer_views.add_new(client_handle, buffer.handle())
+            let buffer_handle = buffer.handle();
+            match buffer.discard_and_reload_from_file(
+                &mut self.word_database,
+                &mut self.events,
+                self.config.max_open_size,
+            ) {
+                Err(BufferReadError::FileTooBig(len)) => {
+                    self.buffers.remove(buffer_handle, &mut self.word_database);
+                    Err(BufferReadError::FileTooBig(len))
+                }
+                _ => Ok(self.buffer_views.add_new(client_handle, buffer_handle)),
+            }
         }
     }
 
@@ -219,12 +230,16 @@ impl Editor {
     }
 
     pub fn on_pre_render(&mut self, clients: &mut ClientManager) -> bool {
-        let picker_height = self
-            .picker
-            .update_scroll(self.config.picker_max_height as _);
+        let focused_handle = clients.focused_client();
+
+        let mut picker_max_height = self.config.picker_max_height as usize;
+        if let Some(handle) = focused_handle {
+            let viewport_height = clients.get(handle).viewport_size.1 as usize;
+            picker_max_height = picker_max_height.min(viewport_height.saturating_sub(2));
+        }
+        let picker_height = self.picker.update_scroll(picker_max_height);
 
         let mut needs_redraw = false;
-        let focused_handle = clients.focused_client();
 
         for c in clients.iter_mut() {
             let picker_height = if focused_handle == Some(c.handle()) {
@@ -314,6 +329,28 @@ impl Editor {
     }
 
     pub fn on_idle(&mut self, clients: &mut ClientManager, platform: &mut Platform) {
+        if self.config.autosave && self.buffered_keys.as_slice().is_empty() {
+            for buffer in self.buffers.iter_mut() {
+                if !buffer.needs_save() || buffer.path.as_os_str().is_empty() {
+                    continue;
+                }
+                if buffer.changed_on_disk() {
+                    if buffer.take_changed_on_disk_report() {
+                        self.status_bar.write(MessageKind::Error).fmt(format_args!(
+                            "{:?} changed on disk since it was loaded. not autosaving it",
+                            &buffer.path
+                        ));
+                    }
+                    continue;
+                }
+                if let Err(error) = buffer.save_to_file(None, &mut self.events) {
+                    self.status_bar
+                        .write(MessageKind::Error)
+                        .fmt(format_args!("{}", error));
+                }
+            }
+        }
+
         self.events.enqueue(EditorEvent::Idle);
         self.trigger_event_handlers(platform, clients);
     }
@@ -438,3 +475,166 @@ impl Editor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, sync::mpsc};
+
+    use crate::{buffer_position::BufferPosition, word_database::WordIndicesIter};
+
+    #[test]
+    fn autosave_on_idle() {
+        let mut editor = Editor::new(PathBuf::new());
+        let mut platform = Platform::new(|| (), mpsc::channel().0);
+        let mut clients = ClientManager::default();
+
+        let path = std::env::temp_dir().join("pepper_autosave_on_idle_test.txt");
+        let _ = fs::remove_file(&path);
+        let buffer = editor.buffers.add_new();
+        buffer.capabilities = BufferCapabilities::text();
+        buffer.path.push(&path);
+        buffer.insert_text(
+            &mut editor.word_database,
+            BufferPosition::zero(),
+            "text",
+            &mut editor.events,
+        );
+
+        editor.on_idle(&mut clients, &mut platform);
+        let not_enabled = fs::metadata(&path).is_ok();
+
+        editor.config.autosave = true;
+        assert!(editor.buffered_keys.parse("g").is_ok());
+        editor.on_idle(&mut clients, &mut platform);
+        let pending_keys = fs::metadata(&path).is_ok();
+
+        editor.buffered_keys.0.clear();
+        editor.on_idle(&mut clients, &mut platform);
+        let saved = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(!not_enabled);
+        assert!(!pending_keys);
+        assert_eq!("text\n", saved.unwrap());
+        assert!(editor.buffers.iter().all(|b| !b.needs_save()));
+    }
+
+    #[test]
+    fn autosave_reports_changed_on_disk_once() {
+        let mut editor = Editor::new(PathBuf::new());
+        let mut platform = Platform::new(|| (), mpsc::channel().0);
+        let mut clients = ClientManager::default();
+        editor.config.autosave = true;
+
+        let path = std::env::temp_dir().join("pepper_autosave_changed_on_disk_test.txt");
+        fs::write(&path, "disk").unwrap();
+        let buffer = editor.buffers.add_new();
+        buffer.capabilities = BufferCapabilities::text();
+        buffer.path.push(&path);
+        let loaded = buffer.discard_and_reload_from_file(
+            &mut editor.word_database,
+            &mut editor.events,
+            u64::MAX,
+        );
+        buffer.insert_text(
+            &mut editor.word_database,
+            BufferPosition::zero(),
+            "edit ",
+            &mut editor.events,
+        );
+
+        let modified_time = fs::metadata(&path).unwrap().modified().unwrap();
+        let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_modified(modified_time + std::time::Duration::from_secs(1))
+            .unwrap();
+        drop(file);
+
+        editor.on_idle(&mut clients, &mut platform);
+        let (first_kind, first_message) = editor.status_bar.message();
+        let first_message = first_message.to_string();
+        editor.status_bar.clear();
+        editor.on_idle(&mut clients, &mut platform);
+        let second_message = editor.status_bar.message().1.to_string();
+        let content = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(loaded.is_ok());
+        assert_eq!(MessageKind::Error, first_kind);
+        assert!(first_message.contains("changed on disk"));
+        assert_eq!("", second_message);
+        assert_eq!("disk", content.unwrap());
+        assert!(editor.buffers.iter().all(|b| b.needs_save()));
+    }
+
+    #[test]
+    fn autosave_keeps_trailing_blank_lines() {
+        let mut editor = Editor::new(PathBuf::new());
+        let mut platform = Platform::new(|| (), mpsc::channel().0);
+        let mut clients = ClientManager::default();
+        editor.config.autosave = true;
+        editor.config.trim_trailing_blank_lines = true;
+
+        let path = std::env::temp_dir().join("pepper_autosave_keeps_blank_lines_test.txt");
+        let buffer = editor.buffers.add_new();
+        buffer.capabilities = BufferCapabilities::text();
+        buffer.path.push(&path);
+        buffer.insert_text(
+            &mut editor.word_database,
+            BufferPosition::zero(),
+            "text\n",
+            &mut editor.events,
+        );
+        let buffer_handle = buffer.handle();
+
+        editor.on_idle(&mut clients, &mut platform);
+        let saved = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+
+        let content = editor.buffers.get(buffer_handle).content();
+        assert_eq!(2, content.line_count());
+        assert_eq!("text\n\n", saved.unwrap());
+    }
+
+    #[test]
+    fn picker_height_fits_viewport() {
+        let mut editor = Editor::new(PathBuf::new());
+        let mut platform = Platform::new(|| (), mpsc::channel().0);
+        let mut clients = ClientManager::default();
+        let handle = ClientHandle::from_index(0).unwrap();
+        clients.on_client_joined(handle);
+        let mut resize = |editor: &mut Editor, clients: &mut ClientManager, height| {
+            let event = ClientEvent::Resize(80, height);
+            editor.on_client_event(&mut platform, clients, handle, event);
+            editor.on_pre_render(clients);
+        };
+        resize(&mut editor, &mut clients, 24);
+        clients.focus_client(handle);
+
+        for i in 0..20 {
+            editor
+                .picker
+                .add_custom_entry_fmt(format_args!("entry{}", i));
+        }
+        editor
+            .picker
+            .filter(WordIndicesIter::empty(), "", editor.config.case_matching);
+        editor.picker.move_cursor(-1);
+        editor.picker.move_cursor(-1);
+
+        editor.on_pre_render(&mut clients);
+        let picker_max_height = editor.config.picker_max_height as u16;
+        assert_eq!(24 - 1 - picker_max_height, clients.get(handle).height);
+
+        resize(&mut editor, &mut clients, 4);
+        assert_eq!(1, clients.get(handle).height);
+        assert_eq!(20 - 2, editor.picker.scroll());
+
+        resize(&mut editor, &mut clients, 2);
+        assert_eq!(1, clients.get(handle).height);
+
+        resize(&mut editor, &mut clients, 24);
+        assert_eq!(24 - 1 - picker_max_height, clients.get(handle).height);
+        assert_eq!(20 - picker_max_height as usize, editor.picker.scroll());
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, process::Command};
+use std::{fmt, path::Path, process::Command};
 
 use crate::{
     client::ClientManager,
@@ -62,9 +62,7 @@ impl ReadLine {
                 ReadLinePoll::Pending
             }
             Key::Backspace | Key::Ctrl('h') => {
-                if let Some((last_char_index, _)) = self.input.char_indices().rev().next() {
-                    self.input.truncate(last_char_index);
-                }
+                pop_grapheme(&mut self.input);
                 ReadLinePoll::Pending
             }
             Key::Ctrl('y') => {
@@ -83,7 +81,52 @@ impl ReadLine {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+// this is not full unicode segmentation as we don't ship its tables. it only knows about
+// the latin/symbol combining marks below, variation selectors, skin tones, tags, zwj
+// sequences and flag pairs. combining marks from other scripts (hebrew, arabic, indic,
+// thai, ...) are popped one char at a time
+fn pop_grapheme(text: &mut String) {
+    fn is_regional_indicator(c: char) -> bool {
+        matches!(c, '\u{1f1e6}'..='\u{1f1ff}')
+    }
+
+    fn is_extend(c: char) -> bool {
+        matches!(c,
+            '\u{0300}'..='\u{036f}'
+            | '\u{1ab0}'..='\u{1aff}'
+            | '\u{1dc0}'..='\u{1dff}'
+            | '\u{200c}'..='\u{200d}'
+            | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{fe20}'..='\u{fe2f}'
+            | '\u{1f3fb}'..='\u{1f3ff}'
+            | '\u{e0020}'..='\u{e007f}'
+        )
+    }
+
+    while let Some(c) = text.pop() {
+        if is_extend(c) {
+            continue;
+        }
+        if text.ends_with('\u{200d}') {
+            text.pop();
+            continue;
+        }
+        if is_regional_indicator(c) {
+            let count = text
+                .chars()
+                .rev()
+                .take_while(|&c| is_regional_indicator(c))
+                .count();
+            if count % 2 == 1 {
+                text.pop();
+            }
+        }
+        break;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MessageKind {
     Info,
     Error,
@@ -167,14 +210,55 @@ pub const fn hash_bytes(mut bytes: &[u8]) -> u64 {
     hash
 }
 
-pub fn parse_process_command(command: &str) -> Option<Command> {
+pub enum ProcessCommandError {
+    NoProgram,
+    NoSuchDirectory(String),
+}
+impl fmt::Display for ProcessCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoProgram => f.write_str("no program in process command"),
+            Self::NoSuchDirectory(path) => write!(f, "no such directory '{}'", path),
+        }
+    }
+}
+
+pub fn parse_process_command(command: &str) -> Result<Command, ProcessCommandError> {
+    fn parse_env_var(token: &str) -> Option<(&str, &str)> {
+        let (key, value) = token.split_at(token.find('=')?);
+        if !key.is_empty() && key.chars().all(|c| c == '_' || c.is_ascii_alphanumeric()) {
+            Some((key, &value[1..]))
+        } else {
+            None
+        }
+    }
+
     let mut tokenizer = CommandTokenizer(command);
-    let name = tokenizer.next()?;
+    let mut env_vars = Vec::new();
+    let mut current_dir = None;
+    let name = loop {
+        let token = tokenizer.next().ok_or(ProcessCommandError::NoProgram)?;
+        if let Some(dir) = token.strip_prefix("-cwd=") {
+            if !Path::new(dir).is_dir() {
+                return Err(ProcessCommandError::NoSuchDirectory(dir.into()));
+            }
+            current_dir = Some(dir);
+        } else if let Some(env_var) = parse_env_var(token) {
+            env_vars.push(env_var);
+        } else {
+            break token;
+        }
+    };
+
     let mut command = Command::new(name);
+    command.envs(env_vars);
+    if let Some(dir) = current_dir {
+        command.current_dir(dir);
+    }
     for arg in tokenizer {
         command.arg(arg);
     }
-    Some(command)
+    Ok(command)
 }
 
 pub fn load_config(
@@ -183,9 +267,9 @@ pub fn load_config(
     clients: &mut ClientManager,
     config_name: &str,
     config_content: &str,
-) -> EditorControlFlow {
+) -> Option<EditorControlFlow> {
     for (line_index, line) in config_content.lines().enumerate() {
-        if line.is_empty() || line.starts_with('#') {
+        if line.trim().is_empty() {
             continue;
         }
 
@@ -196,7 +280,7 @@ pub fn load_config(
         match result {
             Ok(flow) => match flow {
                 EditorControlFlow::Continue => (),
-                _ => return flow,
+                _ => return Some(flow),
             },
             Err(error) => {
                 editor
@@ -209,10 +293,118 @@ pub fn load_config(
                         line,
                         error
                     ));
-                break;
+                return None;
             }
         }
     }
 
-    EditorControlFlow::Continue
+    Some(EditorControlFlow::Continue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn pop_graphemes() {
+        let mut text = String::from("ab\u{e9}e\u{301}\u{1f1e7}\u{1f1f7}\u{1f1fa}\u{1f1f8}");
+        pop_grapheme(&mut text);
+        assert_eq!("ab\u{e9}e\u{301}\u{1f1e7}\u{1f1f7}", text);
+        text.push('\u{1f1e6}');
+        pop_grapheme(&mut text);
+        assert_eq!("ab\u{e9}e\u{301}\u{1f1e7}\u{1f1f7}", text);
+        pop_grapheme(&mut text);
+
+        text.push_str("\u{1f44d}\u{1f3fd}");
+        text.push_str("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{2764}\u{fe0f}");
+
+        pop_grapheme(&mut text);
+        assert!(text.ends_with('\u{1f467}'));
+        pop_grapheme(&mut text);
+        assert!(text.ends_with('\u{1f3fd}'));
+        pop_grapheme(&mut text);
+        assert_eq!("ab\u{e9}e\u{301}", text);
+        pop_grapheme(&mut text);
+        assert_eq!("ab\u{e9}", text);
+        pop_grapheme(&mut text);
+        assert_eq!("ab", text);
+        pop_grapheme(&mut text);
+        pop_grapheme(&mut text);
+        assert_eq!("", text);
+        pop_grapheme(&mut text);
+        assert_eq!("", text);
+    }
+
+    fn parse(command: &str) -> Command {
+        match parse_process_command(command) {
+            Ok(command) => command,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    #[test]
+    fn process_command_parsing() {
+        let command = parse("cmd arg0 'arg 1'");
+        assert_eq!("cmd", command.get_program());
+        assert_eq!(
+            vec!["arg0", "arg 1"],
+            command.get_args().collect::<Vec<_>>()
+        );
+        assert_eq!(0, command.get_envs().count());
+
+        let command = parse("VAR_0=a 'VAR1=b c' EMPTY= cmd X=y");
+        assert_eq!("cmd", command.get_program());
+        assert_eq!(vec!["X=y"], command.get_args().collect::<Vec<_>>());
+        let mut envs = command.get_envs().collect::<Vec<_>>();
+        envs.sort();
+        assert_eq!(
+            vec![
+                (OsStr::new("EMPTY"), Some(OsStr::new(""))),
+                (OsStr::new("VAR1"), Some(OsStr::new("b c"))),
+                (OsStr::new("VAR_0"), Some(OsStr::new("a"))),
+            ],
+            envs
+        );
+
+        let command = parse("=a cmd");
+        assert_eq!("=a", command.get_program());
+
+        let dir = std::env::temp_dir();
+        let command = format!("'-cwd={}' cmd", dir.to_str().unwrap());
+        let command = parse(&command);
+        assert_eq!(Some(dir.as_path()), command.get_current_dir());
+
+        assert!(matches!(
+            parse_process_command("-cwd=no/such/dir cmd"),
+            Err(ProcessCommandError::NoSuchDirectory(_))
+        ));
+        assert!(matches!(
+            parse_process_command("VAR=a"),
+            Err(ProcessCommandError::NoProgram)
+        ));
+        assert!(matches!(
+            parse_process_command(""),
+            Err(ProcessCommandError::NoProgram)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn process_command_env_and_cwd() {
+        let dir = std::env::temp_dir().join("pepper_process_command_cwd_test");
+        let _ = std::fs::create_dir(&dir);
+        let command = format!(
+            "'-cwd={}' PEPPER_TEST_VAR=value sh -c 'pwd -P; echo $PEPPER_TEST_VAR'",
+            dir.to_str().unwrap()
+        );
+        let output = parse(&command).output();
+        let expected_dir = dir.canonicalize();
+        let _ = std::fs::remove_dir(&dir);
+
+        let output = String::from_utf8(output.unwrap().stdout).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(expected_dir.unwrap().to_str(), lines.next());
+        assert_eq!(Some("value"), lines.next());
+    }
 }
@@ -184,6 +184,7 @@ impl<'a> Iterator for KeyParser<'a> {
                     .map(|(i, _)| i)
                     .unwrap_or(0);
                 self.raw = "";
+                self.chars = self.raw.chars();
                 Some(Err(KeyParseAllError { index, error }))
             }
         }
@@ -619,7 +620,11 @@ impl ClientEventIter {
                 self.read_len = buf.len() - slice.len();
                 Some(event)
             }
-            Err(_) => None,
+            Err(DeserializeError::InsufficientData) => None,
+            Err(DeserializeError::InvalidData) => {
+                self.read_len = buf.len();
+                None
+            }
         }
     }
 
@@ -669,6 +674,7 @@ mod tests {
         assert_eq!(Key::PageUp, parse_key(&mut "<pageup>".chars()).unwrap());
         assert_eq!(Key::PageDown, parse_key(&mut "<pagedown>".chars()).unwrap());
         assert_eq!(Key::Tab, parse_key(&mut "<tab>".chars()).unwrap());
+        assert_eq!(Key::Char('\t'), parse_key(&mut "\t".chars()).unwrap());
         assert_eq!(Key::Delete, parse_key(&mut "<delete>".chars()).unwrap());
         assert_eq!(Key::Esc, parse_key(&mut "<esc>".chars()).unwrap());
 
@@ -694,6 +700,11 @@ mod tests {
         assert_eq!(Key::Char('<'), parse_key(&mut "<less>".chars()).unwrap());
         assert_eq!(Key::Char('>'), parse_key(&mut "<greater>".chars()).unwrap());
         assert_eq!(Key::Char('\\'), parse_key(&mut "\\".chars()).unwrap());
+
+        for key in &[Key::Tab, Key::Char('\t')] {
+            let s = key.to_string();
+            assert_eq!(*key, parse_key(&mut s.chars()).unwrap());
+        }
     }
 
     #[test]
@@ -728,6 +739,7 @@ mod tests {
         assert_key_serialization(Key::F(99));
         assert_key_serialization(Key::Char('a'));
         assert_key_serialization(Key::Char('z'));
+        assert_key_serialization(Key::Char('\t'));
         assert_key_serialization(Key::Char('A'));
         assert_key_serialization(Key::Char('Z'));
         assert_key_serialization(Key::Char('0'));
@@ -750,6 +762,42 @@ mod tests {
         assert_key_serialization(Key::Esc);
     }
 
+    #[test]
+    fn key_parse_error_index() {
+        fn parse_error(keys: &str) -> KeyParseAllError {
+            let mut parser = KeyParser::new(keys);
+            loop {
+                match parser.next() {
+                    Some(Ok(_)) => (),
+                    Some(Err(error)) => {
+                        assert!(parser.next().is_none());
+                        return error;
+                    }
+                    None => panic!("keys '{}' parsed without errors", keys),
+                }
+            }
+        }
+
+        let error = parse_error("<c-");
+        assert_eq!(2, error.index);
+        assert!(matches!(error.error, KeyParseError::UnexpectedEnd));
+
+        let error = parse_error("ab<c-");
+        assert_eq!(4, error.index);
+        assert!(matches!(error.error, KeyParseError::UnexpectedEnd));
+
+        let error = parse_error("a<c-xy>b");
+        assert_eq!(5, error.index);
+        assert!(matches!(error.error, KeyParseError::InvalidCharacter('y')));
+
+        let error = parse_error("\u{00e9}<c-\u{00e9}>");
+        assert_eq!(5, error.index);
+        assert!(matches!(
+            error.error,
+            KeyParseError::InvalidCharacter('\u{00e9}')
+        ));
+    }
+
     #[test]
     fn client_event_deserialize_splitted() {
         const CHAR: char = 'x';
@@ -796,4 +844,41 @@ mod tests {
         assert_eq!(0, receiver.bufs[client_handle.into_index()].len());
         assert_eq!(EVENT_COUNT, event_count);
     }
+
+    #[test]
+    fn client_event_deserialize_malformed() {
+        let client_handle = ClientHandle::from_index(0).unwrap();
+        let mut bytes = Vec::new();
+        ClientEvent::Command(TargetClient::Sender, "quit").serialize(&mut bytes);
+        let mut receiver = ClientEventReceiver::default();
+
+        let mut events = receiver.receive_events(client_handle, &bytes[..bytes.len() - 1]);
+        assert!(events.next(&receiver).is_none());
+        events.finish(&mut receiver);
+        assert_eq!(
+            bytes.len() - 1,
+            receiver.bufs[client_handle.into_index()].len()
+        );
+
+        let mut events = receiver.receive_events(client_handle, &bytes[bytes.len() - 1..]);
+        assert!(matches!(
+            events.next(&receiver),
+            Some(ClientEvent::Command(TargetClient::Sender, "quit"))
+        ));
+        assert!(events.next(&receiver).is_none());
+        events.finish(&mut receiver);
+        assert_eq!(0, receiver.bufs[client_handle.into_index()].len());
+
+        let mut events = receiver.receive_events(client_handle, &[255, 0, 1, 2, 3]);
+        assert!(events.next(&receiver).is_none());
+        events.finish(&mut receiver);
+        assert_eq!(0, receiver.bufs[client_handle.into_index()].len());
+
+        let mut events = receiver.receive_events(client_handle, &bytes);
+        assert!(matches!(
+            events.next(&receiver),
+            Some(ClientEvent::Command(TargetClient::Sender, "quit"))
+        ));
+        events.finish(&mut receiver);
+    }
 }
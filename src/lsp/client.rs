@@ -1078,11 +1078,20 @@ impl Client {
             NavigationHistory::save_client_snapshot(client, &editor.buffer_views);
 
             let position = symbol.range.start.into();
-            let buffer_view_handle = editor.buffer_view_handle_from_path(
+            let buffer_view_handle = match editor.buffer_view_handle_from_path(
                 client_handle,
                 path,
                 BufferCapabilities::text(),
-            );
+            ) {
+                Ok(handle) => handle,
+                Err(error) => {
+                    editor
+                        .status_bar
+                        .write(MessageKind::Error)
+                        .fmt(format_args!("{}", error));
+                    return;
+                }
+            };
             client.set_buffer_view_handle(Some(buffer_view_handle), &mut editor.events);
 
             let mut cursors = editor
@@ -1113,10 +1122,15 @@ impl Client {
 
         let buffer_path = &editor.buffers.get(buffer_handle).path;
         let text_document = helper::text_document_with_id(&self.root, buffer_path, &mut self.json);
+        let tab_size = if editor.config.indent_with_tabs {
+            editor.config.tab_size
+        } else {
+            editor.config.indent_size
+        };
         let mut options = JsonObject::default();
         options.set(
             "tabSize".into(),
-            JsonValue::Integer(editor.config.tab_size.get() as _),
+            JsonValue::Integer(tab_size.get() as _),
             &mut self.json,
         );
         options.set(
@@ -1340,24 +1354,31 @@ impl Client {
                     false
                 } else if let Some(client_handle) = clients.focused_client() {
                     let client = clients.get_mut(client_handle);
-                    let buffer_view_handle = editor.buffer_view_handle_from_path(
+                    match editor.buffer_view_handle_from_path(
                         client_handle,
                         path,
                         BufferCapabilities::text(),
-                    );
-                    if let Some(range) = params.selection {
-                        let buffer_view = editor.buffer_views.get_mut(buffer_view_handle);
-                        let mut cursors = buffer_view.cursors.mut_guard();
-                        cursors.clear();
-                        cursors.add(Cursor {
-                            anchor: range.start.into(),
-                            position: range.end.into(),
-                        });
-                    }
-                    if let Some(true) = params.take_focus {
-                        client.set_buffer_view_handle(Some(buffer_view_handle), &mut editor.events);
+                    ) {
+                        Ok(buffer_view_handle) => {
+                            if let Some(range) = params.selection {
+                                let buffer_view = editor.buffer_views.get_mut(buffer_view_handle);
+                                let mut cursors = buffer_view.cursors.mut_guard();
+                                cursors.clear();
+                                cursors.add(Cursor {
+                                    anchor: range.start.into(),
+                                    position: range.end.into(),
+                                });
+                            }
+                            if let Some(true) = params.take_focus {
+                                client.set_buffer_view_handle(
+                                    Some(buffer_view_handle),
+                                    &mut editor.events,
+                                );
+                            }
+                            true
+                        }
+                        Err(_) => false,
                     }
-                    true
                 } else {
                     false
                 };
@@ -1664,11 +1685,20 @@ impl Client {
                         let client = clients.get_mut(client_handle);
                         NavigationHistory::save_client_snapshot(client, &editor.buffer_views);
 
-                        let buffer_view_handle = editor.buffer_view_handle_from_path(
+                        let buffer_view_handle = match editor.buffer_view_handle_from_path(
                             client_handle,
                             path,
                             BufferCapabilities::text(),
-                        );
+                        ) {
+                            Ok(handle) => handle,
+                            Err(error) => {
+                                editor
+                                    .status_bar
+                                    .write(MessageKind::Error)
+                                    .fmt(format_args!("{}", error));
+                                return Ok(());
+                            }
+                        };
                         let position = location.range.start.into();
                         let mut cursors = editor
                             .buffer_views
@@ -1762,6 +1792,16 @@ impl Client {
                     BufferCapabilities::text(),
                 );
                 editor.string_pool.release(buffer_name);
+                let buffer_view_handle = match buffer_view_handle {
+                    Ok(handle) => handle,
+                    Err(error) => {
+                        editor
+                            .status_bar
+                            .write(MessageKind::Error)
+                            .fmt(format_args!("{}", error));
+                        return Ok(());
+                    }
+                };
 
                 let mut count = 0;
                 let mut context_buffer = BufferContent::new();
@@ -2145,7 +2185,11 @@ impl Client {
                     WordKind::Identifier => word.text,
                     _ => "",
                 };
-                editor.picker.filter(WordIndicesIter::empty(), filter);
+                editor.picker.filter(
+                    WordIndicesIter::empty(),
+                    filter,
+                    editor.config.case_matching,
+                );
                 Ok(())
             }
             _ => Ok(()),
@@ -2767,12 +2811,15 @@ impl ClientManager {
                     continue;
                 }
                 let command = match parse_process_command(&recipe.command) {
-                    Some(command) => command,
-                    None => {
+                    Ok(command) => command,
+                    Err(error) => {
                         editor
                             .status_bar
                             .write(MessageKind::Error)
-                            .fmt(format_args!("invalid lsp command '{}'", &recipe.command));
+                            .fmt(format_args!(
+                                "invalid lsp command '{}': {}",
+                                &recipe.command, error
+                            ));
                         continue;
                     }
                 };
@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::{
-    buffer::{BufferCapabilities, BufferHandle},
+    buffer::{BufferCapabilities, BufferHandle, BufferReadError},
     buffer_position::{BufferPosition, BufferRange},
     editor::Editor,
     editor_utils::MessageKind,
@@ -622,11 +622,20 @@ impl WorkspaceEdit {
                             buffer.capabilities.can_save = true;
                             buffer.path.clear();
                             buffer.path.push(path);
-                            let _ = buffer.discard_and_reload_from_file(
-                                &mut editor.word_database,
-                                &mut editor.events,
-                            );
-                            (true, buffer.handle())
+                            let buffer_handle = buffer.handle();
+                            if let Err(BufferReadError::FileTooBig(_)) = buffer
+                                .discard_and_reload_from_file(
+                                    &mut editor.word_database,
+                                    &mut editor.events,
+                                    editor.config.max_open_size,
+                                )
+                            {
+                                editor
+                                    .buffers
+                                    .remove(buffer_handle, &mut editor.word_database);
+                                continue;
+                            }
+                            (true, buffer_handle)
                         }
                     };
 
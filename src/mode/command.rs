@@ -3,9 +3,9 @@ use std::fs;
 use crate::{
     command::{CommandManager, CommandTokenizer, CompletionSource},
     editor::{EditorControlFlow, KeysIterator},
-    editor_utils::{hash_bytes, ReadLinePoll},
+    editor_utils::{hash_bytes, MessageKind, ReadLinePoll},
     mode::{Mode, ModeContext, ModeKind, ModeState},
-    picker::Picker,
+    picker::{EntryKind, Picker},
     platform::Key,
     word_database::WordIndicesIter,
 };
@@ -20,6 +20,7 @@ pub struct State {
     completion_index: usize,
     completion_source: CompletionSource,
     completion_path_hash: Option<u64>,
+    typed_input: String,
 }
 
 impl Default for State {
@@ -29,6 +30,7 @@ impl Default for State {
             completion_index: 0,
             completion_source: CompletionSource::Custom(&[]),
             completion_path_hash: None,
+            typed_input: String::new(),
         }
     }
 }
@@ -40,6 +42,7 @@ impl ModeState for State {
         state.completion_index = 0;
         state.completion_source = CompletionSource::Custom(&[]);
         state.completion_path_hash = None;
+        state.typed_input.clear();
 
         ctx.editor.read_line.set_prompt(":");
         ctx.editor.read_line.input_mut().clear();
@@ -63,30 +66,15 @@ impl ModeState for State {
                 keys.index = keys.index.saturating_sub(1);
                 match keys.next(&ctx.editor.buffered_keys) {
                     Key::Ctrl('n' | 'j') => match state.read_state {
-                        ReadCommandState::NavigatingHistory(ref mut i) => {
-                            *i = ctx
-                                .editor
-                                .commands
-                                .history_len()
-                                .saturating_sub(1)
-                                .min(*i + 1);
-                            let entry = ctx.editor.commands.history_entry(*i);
-                            let input = ctx.editor.read_line.input_mut();
-                            input.clear();
-                            input.push_str(entry);
-                        }
+                        ReadCommandState::NavigatingHistory(_) => navigate_history(ctx, 1),
                         ReadCommandState::TypingCommand => apply_completion(ctx, 1),
                     },
                     Key::Ctrl('p' | 'k') => match state.read_state {
-                        ReadCommandState::NavigatingHistory(ref mut i) => {
-                            *i = i.saturating_sub(1);
-                            let entry = ctx.editor.commands.history_entry(*i);
-                            let input = ctx.editor.read_line.input_mut();
-                            input.clear();
-                            input.push_str(entry);
-                        }
+                        ReadCommandState::NavigatingHistory(_) => navigate_history(ctx, -1),
                         ReadCommandState::TypingCommand => apply_completion(ctx, -1),
                     },
+                    Key::Down => navigate_history(ctx, 1),
+                    Key::Up => navigate_history(ctx, -1),
                     _ => update_autocomplete_entries(ctx),
                 }
             }
@@ -96,7 +84,7 @@ impl ModeState for State {
                 ctx.editor.commands.add_to_history(input);
 
                 let mut command = ctx.editor.string_pool.acquire_with(input);
-                let flow = CommandManager::eval(
+                let result = CommandManager::try_eval(
                     ctx.editor,
                     ctx.platform,
                     ctx.clients,
@@ -105,6 +93,17 @@ impl ModeState for State {
                 );
                 ctx.editor.string_pool.release(command);
 
+                let flow = match result {
+                    Ok(flow) => flow,
+                    Err(error) => {
+                        ctx.editor
+                            .status_bar
+                            .write(MessageKind::Error)
+                            .fmt(format_args!("{}", error));
+                        return Some(EditorControlFlow::Continue);
+                    }
+                };
+
                 if ctx.editor.mode.kind() == ModeKind::Command {
                     Mode::change_to(ctx, ModeKind::default());
                 }
@@ -117,6 +116,35 @@ impl ModeState for State {
     }
 }
 
+fn navigate_history(ctx: &mut ModeContext, offset: isize) {
+    let state = &mut ctx.editor.mode.command_state;
+    let history_len = ctx.editor.commands.history_len();
+    let index = match state.read_state {
+        ReadCommandState::NavigatingHistory(index) => index,
+        ReadCommandState::TypingCommand => {
+            state.typed_input.clear();
+            state.typed_input.push_str(ctx.editor.read_line.input());
+            history_len
+        }
+    };
+
+    let index = if offset < 0 {
+        index.saturating_sub((-offset) as _)
+    } else {
+        history_len.min(index + offset as usize)
+    };
+    state.read_state = ReadCommandState::NavigatingHistory(index);
+
+    let entry = if index < history_len {
+        ctx.editor.commands.history_entry(index)
+    } else {
+        &state.typed_input
+    };
+    let input = ctx.editor.read_line.input_mut();
+    input.clear();
+    input.push_str(entry);
+}
+
 fn apply_completion(ctx: &mut ModeContext, cursor_movement: isize) {
     ctx.editor.picker.move_cursor(cursor_movement);
     if let Some((_, entry)) = ctx.editor.picker.current_entry(&ctx.editor.word_database) {
@@ -197,13 +225,22 @@ fn update_autocomplete_entries(ctx: &mut ModeContext) {
         match completion_source {
             CompletionSource::Commands => {
                 for command in ctx.editor.commands.builtin_commands() {
-                    ctx.editor.picker.add_custom_entry(command.name);
+                    ctx.editor
+                        .picker
+                        .add_custom_entry_with_kind(command.name, EntryKind::Command);
+                }
+                for (alias, _) in ctx.editor.commands.aliases.iter() {
+                    ctx.editor
+                        .picker
+                        .add_custom_entry_with_kind(alias, EntryKind::Alias);
                 }
             }
             CompletionSource::Buffers => {
                 for buffer in ctx.editor.buffers.iter() {
                     if let Some(path) = buffer.path.to_str() {
-                        ctx.editor.picker.add_custom_entry(path);
+                        ctx.editor
+                            .picker
+                            .add_custom_entry_with_kind(path, EntryKind::Buffer);
                     }
                 }
             }
@@ -230,7 +267,7 @@ fn update_autocomplete_entries(ctx: &mut ModeContext) {
                     Err(_) => return,
                 };
                 if let Some(entry) = entry.to_str() {
-                    picker.add_custom_entry(entry);
+                    picker.add_custom_entry_with_kind(entry, EntryKind::File);
                 }
             }
         }
@@ -251,5 +288,103 @@ fn update_autocomplete_entries(ctx: &mut ModeContext) {
     }
 
     state.completion_source = completion_source;
-    ctx.editor.picker.filter(WordIndicesIter::empty(), pattern);
+    ctx.editor.picker.filter(
+        WordIndicesIter::empty(),
+        pattern,
+        ctx.editor.config.case_matching,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{path::PathBuf, sync::mpsc};
+
+    use crate::{
+        client::{ClientHandle, ClientManager},
+        editor::Editor,
+        platform::Platform,
+    };
+
+    struct TestContext {
+        editor: Editor,
+        platform: Platform,
+        clients: ClientManager,
+        client_handle: ClientHandle,
+    }
+    impl TestContext {
+        fn new() -> Self {
+            let editor = Editor::new(PathBuf::new());
+            let platform = Platform::new(|| (), mpsc::channel().0);
+            let mut clients = ClientManager::default();
+            let client_handle = ClientHandle::from_index(0).unwrap();
+            clients.on_client_joined(client_handle);
+
+            Self {
+                editor,
+                platform,
+                clients,
+                client_handle,
+            }
+        }
+
+        fn execute_keys(&mut self, keys: &str) {
+            let keys = match self.editor.buffered_keys.parse(keys) {
+                Ok(keys) => keys,
+                Err(_) => panic!("could not parse keys '{}'", keys),
+            };
+            self.editor.execute_keys(
+                &mut self.platform,
+                &mut self.clients,
+                self.client_handle,
+                keys,
+            );
+        }
+    }
+
+    #[test]
+    fn keep_input_on_error() {
+        let mut ctx = TestContext::new();
+
+        ctx.execute_keys(":invalid-command<enter>");
+        assert_eq!(ModeKind::Command, ctx.editor.mode.kind());
+        assert_eq!("invalid-command", ctx.editor.read_line.input());
+        assert_eq!(MessageKind::Error, ctx.editor.status_bar.message().0);
+
+        ctx.execute_keys("<c-u>print ok<enter>");
+        assert_eq!(ModeKind::default(), ctx.editor.mode.kind());
+        assert_eq!("", ctx.editor.read_line.input());
+        assert_eq!((MessageKind::Info, "ok"), ctx.editor.status_bar.message());
+    }
+
+    #[test]
+    fn history_keeps_typed_input() {
+        let mut ctx = TestContext::new();
+
+        ctx.execute_keys(":print first<enter>:print ok<enter>");
+        ctx.execute_keys(":print half");
+        ctx.execute_keys("<up>");
+        assert_eq!("print ok", ctx.editor.read_line.input());
+        ctx.execute_keys("<c-p>");
+        assert_eq!("print first", ctx.editor.read_line.input());
+        ctx.execute_keys("<down>");
+        assert_eq!("print ok", ctx.editor.read_line.input());
+        ctx.execute_keys("<c-n>");
+        assert_eq!("print half", ctx.editor.read_line.input());
+        ctx.execute_keys("<c-n>");
+        assert_eq!("print half", ctx.editor.read_line.input());
+    }
+
+    #[test]
+    fn completion_entry_kinds() {
+        let mut ctx = TestContext::new();
+        ctx.editor.commands.aliases.add("prn", "print");
+
+        ctx.execute_keys(":pr");
+
+        let editor = &ctx.editor;
+        let entries: Vec<_> = editor.picker.entries(&editor.word_database).collect();
+        assert!(entries.contains(&(EntryKind::Command, "print")));
+        assert!(entries.contains(&(EntryKind::Alias, "prn")));
+    }
 }
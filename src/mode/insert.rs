@@ -61,6 +61,7 @@ impl ModeState for State {
 
         let key = keys.next(&ctx.editor.buffered_keys);
         let register = ctx.editor.registers.get_mut(AUTO_MACRO_REGISTER);
+        let register_len = register.len();
         let _ = write!(register, "{}", key);
 
         match key {
@@ -118,7 +119,7 @@ impl ModeState for State {
                 let text = if ctx.editor.config.indent_with_tabs {
                     "\t"
                 } else {
-                    let len = ctx.editor.config.tab_size.get() as usize;
+                    let len = ctx.editor.config.indent_size.get() as usize;
                     unsafe { std::str::from_utf8_unchecked(&SPACES_BUF[..len]) }
                 };
 
@@ -132,6 +133,32 @@ impl ModeState for State {
                         &mut ctx.editor.events,
                     );
             }
+            Key::Ctrl('v') => {
+                let key = keys.next(&ctx.editor.buffered_keys);
+                let register = ctx.editor.registers.get_mut(AUTO_MACRO_REGISTER);
+                if let Key::None = key {
+                    register.truncate(register_len);
+                    return None;
+                }
+                let _ = write!(register, "{}", key);
+
+                let mut buf = [0; std::mem::size_of::<char>()];
+                let text = match key {
+                    Key::Tab => "\t",
+                    Key::Char(c) => c.encode_utf8(&mut buf),
+                    _ => return Some(EditorControlFlow::Continue),
+                };
+
+                ctx.editor
+                    .buffer_views
+                    .get(handle)
+                    .insert_text_at_cursor_positions(
+                        &mut ctx.editor.buffers,
+                        &mut ctx.editor.word_database,
+                        text,
+                        &mut ctx.editor.events,
+                    );
+            }
             Key::Enter | Key::Ctrl('m') => {
                 let buffer_view = ctx.editor.buffer_views.get(handle);
                 let cursor_count = buffer_view.cursors[..].len();
@@ -145,7 +172,9 @@ impl ModeState for State {
                     let indentation_word = buffer
                         .content()
                         .word_at(BufferPosition::line_col(position.line_index, 0));
-                    if indentation_word.kind == WordKind::Whitespace {
+                    if ctx.editor.config.auto_indent
+                        && indentation_word.kind == WordKind::Whitespace
+                    {
                         let indentation_len = position
                             .column_byte_index
                             .min(indentation_word.text.len() as _);
@@ -323,14 +352,17 @@ fn update_completions(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandl
     }
 
     match ctx.editor.mode.insert_state.lsp_client_handle {
-        Some(_) => ctx
-            .editor
-            .picker
-            .filter(WordIndicesIter::empty(), word.text),
+        Some(_) => ctx.editor.picker.filter(
+            WordIndicesIter::empty(),
+            word.text,
+            ctx.editor.config.case_matching,
+        ),
         None => {
-            ctx.editor
-                .picker
-                .filter(ctx.editor.word_database.word_indices(), word.text);
+            ctx.editor.picker.filter(
+                ctx.editor.word_database.word_indices(),
+                word.text,
+                ctx.editor.config.case_matching,
+            );
             if ctx.editor.picker.cursor().is_none() {
                 ctx.editor.picker.move_cursor(0);
             }
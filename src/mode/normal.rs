@@ -83,6 +83,12 @@ impl State {
         let state = &mut ctx.editor.mode.normal_state;
         let keys_from_index = keys.index;
         match keys.next(&ctx.editor.buffered_keys) {
+            Key::Char('0') => ctx.editor.buffer_views.get_mut(handle).move_cursors(
+                &ctx.editor.buffers,
+                CursorMovement::Home,
+                state.movement_kind,
+                ctx.editor.config.tab_size,
+            ),
             Key::Char('h') => ctx.editor.buffer_views.get_mut(handle).move_cursors(
                 &ctx.editor.buffers,
                 CursorMovement::ColumnsBackward(state.count.max(1) as _),
@@ -503,11 +509,20 @@ impl State {
                                 );
                             }
 
-                            let handle = ctx.editor.buffer_view_handle_from_path(
+                            let handle = match ctx.editor.buffer_view_handle_from_path(
                                 ctx.client_handle,
                                 path,
                                 BufferCapabilities::text(),
-                            );
+                            ) {
+                                Ok(handle) => handle,
+                                Err(error) => {
+                                    ctx.editor
+                                        .status_bar
+                                        .write(MessageKind::Error)
+                                        .fmt(format_args!("{}", error));
+                                    continue;
+                                }
+                            };
                             let mut cursors =
                                 ctx.editor.buffer_views.get_mut(handle).cursors.mut_guard();
                             cursors.clear();
@@ -783,7 +798,7 @@ impl State {
                                 Some((i, c @ '\t')) => i + c.len_utf8(),
                                 Some((i, c @ ' ')) => {
                                     match chars
-                                        .take(ctx.editor.config.tab_size.get() as usize - 1)
+                                        .take(ctx.editor.config.indent_size.get() as usize - 1)
                                         .take_while(|(_, c)| *c == ' ')
                                         .last()
                                     {
@@ -817,8 +832,8 @@ impl State {
                     let count = state.count.max(1) as _;
                     std::iter::repeat('\t').take(count)
                 } else {
-                    let tab_size = ctx.editor.config.tab_size.get() as usize;
-                    let count = state.count.max(1) as usize * tab_size;
+                    let indent_size = ctx.editor.config.indent_size.get() as usize;
+                    let count = state.count.max(1) as usize * indent_size;
                     std::iter::repeat(' ').take(count)
                 };
 
@@ -971,6 +986,61 @@ impl State {
                         }
                     }
                 }
+                Key::Char('N') => {
+                    let buffer_view = ctx.editor.buffer_views.get_mut(handle);
+                    let buffer = ctx.editor.buffers.get(buffer_view.buffer_handle).content();
+                    let mut cursors = buffer_view.cursors.mut_guard();
+
+                    let mut text = ctx.editor.string_pool.acquire();
+                    for _ in 0..state.count.max(1) {
+                        let main_cursor = *cursors.main_cursor();
+                        let range = main_cursor.to_range();
+                        text.clear();
+                        buffer.append_range_text_to_string(range, &mut text);
+                        match buffer.find_text_from(range.to, &text) {
+                            Some(found) if found.from != range.from => {
+                                cursors.add(if main_cursor.anchor <= main_cursor.position {
+                                    Cursor {
+                                        anchor: found.from,
+                                        position: found.to,
+                                    }
+                                } else {
+                                    Cursor {
+                                        anchor: found.to,
+                                        position: found.from,
+                                    }
+                                });
+                            }
+                            _ => break,
+                        }
+                    }
+                    ctx.editor.string_pool.release(text);
+                }
+                Key::Char('b') => {
+                    let buffer_view = ctx.editor.buffer_views.get_mut(handle);
+                    let buffer = ctx.editor.buffers.get(buffer_view.buffer_handle).content();
+                    let mut cursors = buffer_view.cursors.mut_guard();
+
+                    let main_cursor = *cursors.main_cursor();
+                    let range = main_cursor.to_range();
+                    cursors.clear();
+                    cursors.add_block(
+                        range.from.line_index,
+                        range.to.line_index,
+                        main_cursor.anchor.column_byte_index,
+                        main_cursor.position.column_byte_index,
+                        |i| buffer.line_at(i as _).as_str().len() as _,
+                    );
+
+                    for cursor in &mut cursors[..] {
+                        let line = buffer.line_at(cursor.anchor.line_index as _).as_str();
+                        for position in [&mut cursor.anchor, &mut cursor.position] {
+                            while !line.is_char_boundary(position.column_byte_index as _) {
+                                position.column_byte_index -= 1;
+                            }
+                        }
+                    }
+                }
                 Key::Char('n') => {
                     let cursors = &mut ctx.editor.buffer_views.get_mut(handle).cursors;
                     let index = cursors.main_cursor_index();
@@ -1025,7 +1095,7 @@ impl State {
             Key::Char('Y') => {
                 let mut text = ctx.editor.string_pool.acquire();
                 ctx.platform.read_from_clipboard(&mut text);
-                paste_text(ctx, handle, &text);
+                paste_text(ctx, handle, &text, false);
                 ctx.editor.string_pool.release(text);
                 return Some(EditorControlFlow::Continue);
             }
@@ -1035,20 +1105,23 @@ impl State {
                     let key = c.to_ascii_lowercase();
                     if key == c {
                         if let Some(key) = RegisterKey::from_char(key) {
+                            let linewise = has_whole_line_selections(ctx, handle);
                             let mut text = ctx.editor.string_pool.acquire();
                             copy_text(ctx, handle, &mut text);
                             if !text.is_empty() {
                                 let register = ctx.editor.registers.get_mut(key);
                                 register.clear();
                                 register.push_str(&text);
+                                ctx.editor.registers.set_linewise(key, linewise);
                             }
                             ctx.editor.string_pool.release(text);
                         }
                     } else {
                         if let Some(key) = RegisterKey::from_char(key) {
                             let register = ctx.editor.registers.get(key);
+                            let linewise = ctx.editor.registers.is_linewise(key);
                             let text = ctx.editor.string_pool.acquire_with(register);
-                            paste_text(ctx, handle, &text);
+                            paste_text(ctx, handle, &text, linewise);
                             ctx.editor.string_pool.release(text);
                             return Some(EditorControlFlow::Continue);
                         }
@@ -1276,8 +1349,10 @@ impl ModeState for State {
             }
             Key::Char(c) => {
                 if let Some(n) = c.to_digit(10) {
-                    state.count = state.count.saturating_mul(10).saturating_add(n);
-                    return Some(EditorControlFlow::Continue);
+                    if n > 0 || state.count > 0 {
+                        state.count = state.count.saturating_mul(10).saturating_add(n);
+                        return Some(EditorControlFlow::Continue);
+                    }
                 }
             }
             _ => (),
@@ -1292,7 +1367,9 @@ impl ModeState for State {
             match ctx.clients.get(ctx.client_handle).buffer_view_handle() {
                 Some(buffer_view_handle) => {
                     keys.index = previous_index;
+                    apply_max_cursors(ctx, buffer_view_handle);
                     let op = Self::on_client_keys_with_buffer_view(ctx, keys, buffer_view_handle);
+                    report_dropped_cursors(ctx, buffer_view_handle);
                     show_hovered_diagnostic(ctx);
                     op
                 }
@@ -1302,6 +1379,25 @@ impl ModeState for State {
     }
 }
 
+pub fn apply_max_cursors(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandle) {
+    let max_cursors = ctx.editor.config.max_cursors.get() as usize;
+    let buffer_view = ctx.editor.buffer_views.get_mut(buffer_view_handle);
+    buffer_view.cursors.set_max_len(max_cursors);
+}
+
+pub fn report_dropped_cursors(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandle) {
+    let buffer_view = ctx.editor.buffer_views.get_mut(buffer_view_handle);
+    if buffer_view.cursors.take_dropped_cursors() {
+        ctx.editor
+            .status_bar
+            .write(MessageKind::Info)
+            .fmt(format_args!(
+                "too many cursors. stopped at {} of them",
+                ctx.editor.config.max_cursors
+            ));
+    }
+}
+
 fn copy_text(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandle, text: &mut String) {
     let state = &mut ctx.editor.mode.normal_state;
     let buffer_view = ctx.editor.buffer_views.get(buffer_view_handle);
@@ -1312,9 +1408,35 @@ fn copy_text(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandle, text:
     state.movement_kind = CursorMovementKind::PositionAndAnchor;
 }
 
-fn paste_text(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandle, text: &str) {
+fn has_whole_line_selections(ctx: &ModeContext, buffer_view_handle: BufferViewHandle) -> bool {
+    let buffer_view = ctx.editor.buffer_views.get(buffer_view_handle);
+    let content = ctx.editor.buffers.get(buffer_view.buffer_handle).content();
+    buffer_view.cursors[..].iter().all(|c| {
+        let range = c.to_range();
+        let last_line_len = content.line_at(range.to.line_index as _).as_str().len();
+        range.from != range.to
+            && range.from.column_byte_index == 0
+            && range.to.column_byte_index as usize == last_line_len
+    })
+}
+
+fn paste_text(
+    ctx: &mut ModeContext,
+    buffer_view_handle: BufferViewHandle,
+    text: &str,
+    linewise: bool,
+) {
     let state = &mut ctx.editor.mode.normal_state;
     let buffer_view = ctx.editor.buffer_views.get(buffer_view_handle);
+    if linewise
+        && buffer_view.cursors[..]
+            .iter()
+            .all(|c| c.anchor == c.position)
+    {
+        paste_lines_below(ctx, buffer_view_handle, text);
+        return;
+    }
+
     buffer_view.delete_text_in_cursor_ranges(
         &mut ctx.editor.buffers,
         &mut ctx.editor.word_database,
@@ -1357,6 +1479,43 @@ fn paste_text(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandle, text:
         .commit_edits();
 }
 
+fn paste_lines_below(ctx: &mut ModeContext, buffer_view_handle: BufferViewHandle, text: &str) {
+    let state = &mut ctx.editor.mode.normal_state;
+    state.movement_kind = CursorMovementKind::PositionAndAnchor;
+    state.is_recording_auto_macro = false;
+
+    let buffer_view = ctx.editor.buffer_views.get(buffer_view_handle);
+    let cursors = &buffer_view.cursors[..];
+    let ranges = &state.last_copy_ranges[..];
+    let split_text =
+        state.last_copy_hash == hash_bytes(text.as_bytes()) && ranges.len() == cursors.len();
+
+    let buffer = ctx.editor.buffers.get_mut(buffer_view.buffer_handle);
+    let mut lines = ctx.editor.string_pool.acquire();
+    for (i, cursor) in cursors.iter().enumerate().rev() {
+        let text = if split_text {
+            &text[ranges[i].0 as usize..ranges[i].1 as usize]
+        } else {
+            text
+        };
+        lines.clear();
+        lines.push('\n');
+        lines.push_str(text);
+
+        let line_index = cursor.position.line_index;
+        let line_len = buffer.content().line_at(line_index as _).as_str().len();
+        let position = BufferPosition::line_col(line_index, line_len as _);
+        buffer.insert_text(
+            &mut ctx.editor.word_database,
+            position,
+            &lines,
+            &mut ctx.editor.events,
+        );
+    }
+    ctx.editor.string_pool.release(lines);
+    buffer.commit_edits();
+}
+
 fn find_char(ctx: &mut ModeContext, forward: bool) {
     let state = &ctx.editor.mode.normal_state;
     let skip;
@@ -1675,7 +1834,16 @@ fn move_to_diagnostic(ctx: &mut ModeContext, forward: bool) {
                 BufferCapabilities::text(),
             );
             ctx.editor.string_pool.release(path);
-            handle
+            match handle {
+                Ok(handle) => handle,
+                Err(error) => {
+                    ctx.editor
+                        .status_bar
+                        .write(MessageKind::Error)
+                        .fmt(format_args!("{}", error));
+                    return;
+                }
+            }
         }
     };
 
@@ -1696,3 +1864,210 @@ fn move_to_diagnostic(ctx: &mut ModeContext, forward: bool) {
     ctx.editor.mode.normal_state.movement_kind = CursorMovementKind::PositionAndAnchor;
     client.set_buffer_view_handle(Some(buffer_view_handle), &mut ctx.editor.events);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{num::NonZeroU8, path::PathBuf, sync::mpsc};
+
+    use crate::{
+        client::{ClientHandle, ClientManager},
+        platform::Platform,
+    };
+
+    struct TestContext {
+        editor: Editor,
+        platform: Platform,
+        clients: ClientManager,
+        client_handle: ClientHandle,
+        buffer_view_handle: BufferViewHandle,
+    }
+    impl TestContext {
+        fn new() -> Self {
+            let mut editor = Editor::new(PathBuf::new());
+            let platform = Platform::new(|| (), mpsc::channel().0);
+            let mut clients = ClientManager::default();
+            let client_handle = ClientHandle::from_index(0).unwrap();
+            clients.on_client_joined(client_handle);
+
+            let buffer = editor.buffers.add_new();
+            buffer.capabilities = BufferCapabilities::text();
+            let buffer_handle = buffer.handle();
+            let buffer_view_handle = editor.buffer_views.add_new(client_handle, buffer_handle);
+            clients
+                .get_mut(client_handle)
+                .set_buffer_view_handle(Some(buffer_view_handle), &mut editor.events);
+
+            Self {
+                editor,
+                platform,
+                clients,
+                client_handle,
+                buffer_view_handle,
+            }
+        }
+
+        fn execute_keys(&mut self, keys: &str) {
+            let keys = match self.editor.buffered_keys.parse(keys) {
+                Ok(keys) => keys,
+                Err(_) => panic!("could not parse keys '{}'", keys),
+            };
+            self.editor.execute_keys(
+                &mut self.platform,
+                &mut self.clients,
+                self.client_handle,
+                keys,
+            );
+        }
+
+        fn line(&self, index: usize) -> &str {
+            let buffer_handle = self
+                .editor
+                .buffer_views
+                .get(self.buffer_view_handle)
+                .buffer_handle;
+            let content = self.editor.buffers.get(buffer_handle).content();
+            content.line_at(index).as_str()
+        }
+
+        fn cursor_position(&self) -> BufferPosition {
+            let buffer_view = self.editor.buffer_views.get(self.buffer_view_handle);
+            buffer_view.cursors.main_cursor().position
+        }
+    }
+
+    #[test]
+    fn indent_with_indent_size() {
+        let mut ctx = TestContext::new();
+        ctx.editor.config.tab_size = NonZeroU8::new(8).unwrap();
+        ctx.editor.config.indent_size = NonZeroU8::new(2).unwrap();
+        ctx.editor.config.indent_with_tabs = false;
+
+        ctx.execute_keys("ia<esc>");
+        assert_eq!("a", ctx.line(0));
+        ctx.execute_keys("<greater>");
+        assert_eq!("  a", ctx.line(0));
+        ctx.execute_keys("<greater>");
+        assert_eq!("    a", ctx.line(0));
+        ctx.execute_keys("<less>");
+        assert_eq!("  a", ctx.line(0));
+        ctx.execute_keys("2<greater>");
+        assert_eq!("      a", ctx.line(0));
+        ctx.execute_keys("gii<tab><esc>");
+        assert_eq!("        a", ctx.line(0));
+    }
+
+    #[test]
+    fn auto_indent() {
+        let mut ctx = TestContext::new();
+        ctx.execute_keys("i<c-v><tab> a<enter>b<enter><enter>c<esc>");
+        assert_eq!("\t a", ctx.line(0));
+        assert_eq!("\t b", ctx.line(1));
+        assert_eq!("\t ", ctx.line(2));
+        assert_eq!("\t c", ctx.line(3));
+
+        ctx.execute_keys("gii<enter>");
+        assert_eq!("\t ", ctx.line(3));
+        assert_eq!("\t c", ctx.line(4));
+
+        ctx.editor.config.auto_indent = false;
+        ctx.execute_keys("<esc>gli<enter>d<esc>");
+        assert_eq!("\t c", ctx.line(4));
+        assert_eq!("d", ctx.line(5));
+    }
+
+    #[test]
+    fn paste_linewise_register() {
+        let mut ctx = TestContext::new();
+        ctx.execute_keys("ione<enter>two<esc>");
+
+        ctx.execute_keys("gkghvgl<c-y>r");
+        assert!(ctx
+            .editor
+            .registers
+            .is_linewise(RegisterKey::from_char('r').unwrap()));
+        ctx.execute_keys("gjl<c-y>R");
+        assert_eq!("one", ctx.line(0));
+        assert_eq!("two", ctx.line(1));
+        assert_eq!("one", ctx.line(2));
+
+        ctx.execute_keys("gkghvl<c-y>c");
+        assert!(!ctx
+            .editor
+            .registers
+            .is_linewise(RegisterKey::from_char('c').unwrap()));
+        ctx.execute_keys("gjgl<c-y>C");
+        assert_eq!("oneo", ctx.line(2));
+    }
+
+    #[test]
+    fn insert_literal_tab() {
+        let mut ctx = TestContext::new();
+        ctx.editor.config.indent_size = NonZeroU8::new(2).unwrap();
+        ctx.editor.config.indent_with_tabs = false;
+
+        ctx.execute_keys("i<tab>a<c-v><tab>b<c-v>c<esc>");
+        assert_eq!("  a\tbc", ctx.line(0));
+    }
+
+    #[test]
+    fn max_cursors_truncates() {
+        let mut ctx = TestContext::new();
+        ctx.editor.config.max_cursors = NonZeroU8::new(3).unwrap();
+        ctx.execute_keys("ia<enter>b<enter>c<enter>d<enter>e<esc>vkkkk");
+        ctx.execute_keys("cl");
+
+        let buffer_view = ctx.editor.buffer_views.get(ctx.buffer_view_handle);
+        assert_eq!(3, buffer_view.cursors[..].len());
+        assert_eq!(MessageKind::Info, ctx.editor.status_bar.message().0);
+
+        ctx.editor.status_bar.clear();
+        ctx.execute_keys("cdvjcl");
+        let buffer_view = ctx.editor.buffer_views.get(ctx.buffer_view_handle);
+        assert_eq!(2, buffer_view.cursors[..].len());
+        assert_eq!("", ctx.editor.status_bar.message().1);
+    }
+
+    #[test]
+    fn block_selection() {
+        let mut ctx = TestContext::new();
+        ctx.execute_keys("iabcd<enter>e<enter>fghi<esc>gkghlvjjll");
+        ctx.execute_keys("cb");
+
+        let buffer_view = ctx.editor.buffer_views.get(ctx.buffer_view_handle);
+        let ranges: Vec<_> = buffer_view
+            .cursors
+            .ranges()
+            .map(|r| {
+                (
+                    r.from.line_index,
+                    r.from.column_byte_index,
+                    r.to.column_byte_index,
+                )
+            })
+            .collect();
+        assert_eq!(&[(0, 1, 3), (1, 1, 1), (2, 1, 3)], &ranges[..]);
+    }
+
+    #[test]
+    fn count_prefix() {
+        let mut ctx = TestContext::new();
+        ctx.execute_keys("i");
+        for i in 0..10 {
+            ctx.execute_keys(&format!("{}<enter>", i));
+        }
+        ctx.execute_keys("10 11<esc>gkgh");
+        assert_eq!(BufferPosition::line_col(0, 0), ctx.cursor_position());
+
+        ctx.execute_keys("3j");
+        assert_eq!(BufferPosition::line_col(3, 0), ctx.cursor_position());
+        ctx.execute_keys("j");
+        assert_eq!(BufferPosition::line_col(4, 0), ctx.cursor_position());
+        ctx.execute_keys("10j4l");
+        assert_eq!(BufferPosition::line_col(10, 4), ctx.cursor_position());
+        ctx.execute_keys("0");
+        assert_eq!(BufferPosition::line_col(10, 0), ctx.cursor_position());
+        ctx.execute_keys("2k");
+        assert_eq!(BufferPosition::line_col(8, 0), ctx.cursor_position());
+    }
+}
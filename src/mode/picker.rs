@@ -79,11 +79,17 @@ impl ModeState for State {
                     let entry_count = ctx.editor.picker.len() as isize;
                     ctx.editor.picker.move_cursor(entry_count - cursor - 1);
                 }
+                Key::Tab => {
+                    ctx.editor.picker.toggle_mark();
+                    ctx.editor.picker.move_cursor(1);
+                }
                 _ => {
-                    ctx.editor
-                        .picker
-                        .filter(WordIndicesIter::empty(), ctx.editor.read_line.input());
-                    ctx.editor.picker.move_cursor(0);
+                    ctx.editor.picker.filter(
+                        WordIndicesIter::empty(),
+                        ctx.editor.read_line.input(),
+                        ctx.editor.config.case_matching,
+                    );
+                    ctx.editor.picker.select_best();
                 }
             }
         }
@@ -114,26 +120,34 @@ pub mod buffer {
                 }
             }
 
-            let path = match ctx.editor.picker.current_entry(&ctx.editor.word_database) {
-                Some((_, entry)) => entry,
-                _ => {
-                    Mode::change_to(ctx, ModeKind::default());
-                    return Some(EditorControlFlow::Continue);
-                }
-            };
-
-            let client = ctx.clients.get_mut(ctx.client_handle);
-            NavigationHistory::save_client_snapshot(client, &ctx.editor.buffer_views);
+            let mut paths = ctx.editor.string_pool.acquire();
+            for (_, path) in ctx.editor.picker.picked_entries(&ctx.editor.word_database) {
+                paths.push_str(path);
+                paths.push('\n');
+            }
 
-            let path = ctx.editor.string_pool.acquire_with(path);
-            let buffer_view_handle = ctx.editor.buffer_view_handle_from_path(
-                ctx.client_handle,
-                Path::new(&path),
-                BufferCapabilities::text(),
-            );
-            ctx.editor.string_pool.release(path);
+            let mut buffer_view_handle = None;
+            for path in paths.lines() {
+                match ctx.editor.buffer_view_handle_from_path(
+                    ctx.client_handle,
+                    Path::new(path),
+                    BufferCapabilities::text(),
+                ) {
+                    Ok(handle) => buffer_view_handle = Some(handle),
+                    Err(error) => ctx
+                        .editor
+                        .status_bar
+                        .write(MessageKind::Error)
+                        .fmt(format_args!("{}", error)),
+                }
+            }
+            ctx.editor.string_pool.release(paths);
 
-            client.set_buffer_view_handle(Some(buffer_view_handle), &mut ctx.editor.events);
+            if let Some(buffer_view_handle) = buffer_view_handle {
+                let client = ctx.clients.get_mut(ctx.client_handle);
+                NavigationHistory::save_client_snapshot(client, &ctx.editor.buffer_views);
+                client.set_buffer_view_handle(Some(buffer_view_handle), &mut ctx.editor.events);
+            }
 
             Mode::change_to(ctx, ModeKind::default());
             Some(EditorControlFlow::Continue)
@@ -146,7 +160,11 @@ pub mod buffer {
             ctx.editor.picker.add_custom_entry(path);
         }
 
-        ctx.editor.picker.filter(WordIndicesIter::empty(), "");
+        ctx.editor.picker.filter(
+            WordIndicesIter::empty(),
+            "",
+            ctx.editor.config.case_matching,
+        );
         ctx.editor.picker.move_cursor(0);
 
         if ctx.editor.picker.len() > 0 {
@@ -192,6 +210,17 @@ pub mod lsp_definition {
                             BufferCapabilities::text(),
                         );
                         ctx.editor.string_pool.release(path);
+                        let buffer_view_handle = match buffer_view_handle {
+                            Ok(handle) => handle,
+                            Err(error) => {
+                                ctx.editor
+                                    .status_bar
+                                    .write(MessageKind::Error)
+                                    .fmt(format_args!("{}", error));
+                                Mode::change_to(ctx, ModeKind::default());
+                                return Some(EditorControlFlow::Continue);
+                            }
+                        };
                         let mut cursors = ctx
                             .editor
                             .buffer_views
@@ -220,7 +249,11 @@ pub mod lsp_definition {
         }
 
         ctx.editor.read_line.set_prompt("definition:");
-        ctx.editor.picker.filter(WordIndicesIter::empty(), "");
+        ctx.editor.picker.filter(
+            WordIndicesIter::empty(),
+            "",
+            ctx.editor.config.case_matching,
+        );
         ctx.editor.picker.move_cursor(0);
 
         if ctx.editor.picker.len() > 0 {
@@ -270,7 +303,11 @@ pub mod lsp_code_action {
         }
 
         ctx.editor.read_line.set_prompt("code action:");
-        ctx.editor.picker.filter(WordIndicesIter::empty(), "");
+        ctx.editor.picker.filter(
+            WordIndicesIter::empty(),
+            "",
+            ctx.editor.config.case_matching,
+        );
         ctx.editor.picker.move_cursor(0);
 
         if ctx.editor.picker.len() > 0 {
@@ -326,7 +363,11 @@ pub mod lsp_document_symbol {
         }
 
         ctx.editor.read_line.set_prompt("document symbol:");
-        ctx.editor.picker.filter(WordIndicesIter::empty(), "");
+        ctx.editor.picker.filter(
+            WordIndicesIter::empty(),
+            "",
+            ctx.editor.config.case_matching,
+        );
         ctx.editor.picker.move_cursor(0);
 
         if ctx.editor.picker.len() > 0 {
@@ -382,7 +423,11 @@ pub mod lsp_workspace_symbol {
         }
 
         ctx.editor.read_line.set_prompt("workspace symbol:");
-        ctx.editor.picker.filter(WordIndicesIter::empty(), "");
+        ctx.editor.picker.filter(
+            WordIndicesIter::empty(),
+            "",
+            ctx.editor.config.case_matching,
+        );
         ctx.editor.picker.move_cursor(0);
 
         if ctx.editor.picker.len() > 0 {
@@ -287,7 +287,12 @@ pub mod filter_cursors {
 pub mod split_cursors {
     use super::*;
 
-    use crate::{buffer_position::BufferPosition, cursor::Cursor, register::SEARCH_REGISTER};
+    use crate::{
+        buffer_position::BufferPosition,
+        cursor::Cursor,
+        mode::normal::{apply_max_cursors, report_dropped_cursors},
+        register::SEARCH_REGISTER,
+    };
 
     pub fn enter_by_pattern_mode(ctx: &mut ModeContext) {
         fn add_matches(
@@ -404,6 +409,7 @@ pub mod split_cursors {
             Some(handle) => handle,
             None => return,
         };
+        apply_max_cursors(ctx, handle);
         let buffer_view = ctx.editor.buffer_views.get_mut(handle);
         let buffer = ctx
             .editor
@@ -482,6 +488,9 @@ pub mod split_cursors {
                 position: main_cursor_position,
             });
         }
+        drop(cursors);
+
+        report_dropped_cursors(ctx, handle);
     }
 }
 
@@ -593,6 +602,14 @@ pub mod process {
     }
 
     fn spawn_process(ctx: &mut ModeContext, pipe: bool) {
+        if let Err(error) = parse_process_command(ctx.editor.read_line.input()) {
+            ctx.editor
+                .status_bar
+                .write(MessageKind::Error)
+                .fmt(format_args!("{}", error));
+            return;
+        }
+
         let buffer_view_handle = match ctx.clients.get(ctx.client_handle).buffer_view_handle() {
             Some(handle) => handle,
             None => return,
@@ -634,8 +651,8 @@ pub mod process {
         let buffer_view = ctx.editor.buffer_views.get_mut(buffer_view_handle);
         for (i, cursor) in buffer_view.cursors[..].iter().enumerate() {
             let command = match parse_process_command(&command) {
-                Some(command) => command,
-                None => continue,
+                Ok(command) => command,
+                Err(_) => continue,
             };
 
             ctx.editor.buffers.spawn_insert_process(
@@ -714,4 +731,3 @@ fn restore_saved_position(ctx: &mut ModeContext) {
         position,
     });
 }
-
@@ -1,13 +1,36 @@
-use std::fmt;
+use std::{cmp::Reverse, fmt};
 
-use crate::word_database::{WordDatabase, WordIndicesIter};
+use crate::{
+    config::CaseMatching,
+    word_database::{WordDatabase, WordIndicesIter},
+};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum EntrySource {
     Custom(usize),
     WordDatabase(usize),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Text,
+    Command,
+    Alias,
+    Buffer,
+    File,
+}
+impl EntryKind {
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Text => "",
+            Self::Command => "command",
+            Self::Alias => "alias",
+            Self::Buffer => "buffer",
+            Self::File => "file",
+        }
+    }
+}
+
 struct FilteredEntry {
     pub source: EntrySource,
     pub score: u32,
@@ -18,7 +41,9 @@ pub struct Picker {
     fuzzy_matcher: FuzzyMatcher,
     custom_entries_len: usize,
     custom_entries_buffer: Vec<String>,
+    custom_entries_kinds: Vec<EntryKind>,
     filtered_entries: Vec<FilteredEntry>,
+    marked_entries: Vec<EntrySource>,
 
     cursor: Option<usize>,
     scroll: usize,
@@ -41,6 +66,14 @@ impl Picker {
         self.cursor = None;
     }
 
+    pub fn select_best(&mut self) {
+        if self.filtered_entries.is_empty() {
+            self.cursor = None;
+        } else {
+            self.cursor = Some(0);
+        }
+    }
+
     pub fn move_cursor(&mut self, offset: isize) {
         if self.filtered_entries.is_empty() {
             return;
@@ -102,14 +135,17 @@ impl Picker {
     pub fn clear(&mut self) {
         self.custom_entries_len = 0;
         self.filtered_entries.clear();
+        self.marked_entries.clear();
         self.cursor = None;
         self.scroll = 0;
     }
 
-    fn new_custom_entry(&mut self) -> &mut String {
+    fn new_custom_entry(&mut self, kind: EntryKind) -> &mut String {
         if self.custom_entries_len == self.custom_entries_buffer.len() {
             self.custom_entries_buffer.push(String::new());
+            self.custom_entries_kinds.push(kind);
         }
+        self.custom_entries_kinds[self.custom_entries_len] = kind;
         let entry = &mut self.custom_entries_buffer[self.custom_entries_len];
         self.custom_entries_len += 1;
         entry.clear();
@@ -117,28 +153,42 @@ impl Picker {
     }
 
     pub fn add_custom_entry(&mut self, name: &str) {
-        let entry = self.new_custom_entry();
+        self.add_custom_entry_with_kind(name, EntryKind::Text);
+    }
+
+    pub fn add_custom_entry_with_kind(&mut self, name: &str, kind: EntryKind) {
+        let entry = self.new_custom_entry(kind);
         entry.push_str(name);
     }
 
     pub fn add_custom_entry_fmt(&mut self, args: fmt::Arguments) {
-        let entry = self.new_custom_entry();
+        let entry = self.new_custom_entry(EntryKind::Text);
         let _ = fmt::write(entry, args);
     }
 
-    pub fn add_custom_entry_filtered(&mut self, name: &str, pattern: &str) {
+    pub fn add_custom_entry_filtered(
+        &mut self,
+        name: &str,
+        pattern: &str,
+        case_matching: CaseMatching,
+    ) {
         self.add_custom_entry(name);
-        if self.filter_custom_entry(self.custom_entries_len - 1, pattern) {
-            self.filtered_entries
-                .sort_unstable_by(|a, b| b.score.cmp(&a.score));
+        let index = self.custom_entries_len - 1;
+        if self.filter_custom_entry(index, pattern, case_matching) {
+            self.filtered_entries.sort_by_key(|e| Reverse(e.score));
         }
     }
 
-    pub fn filter(&mut self, word_indices: WordIndicesIter, pattern: &str) {
+    pub fn filter(
+        &mut self,
+        word_indices: WordIndicesIter,
+        pattern: &str,
+        case_matching: CaseMatching,
+    ) {
         self.filtered_entries.clear();
 
         for (i, word) in word_indices {
-            let score = self.fuzzy_matcher.score(word, pattern);
+            let score = self.fuzzy_matcher.score(word, pattern, case_matching);
             if score != 0 {
                 self.filtered_entries.push(FilteredEntry {
                     source: EntrySource::WordDatabase(i),
@@ -148,11 +198,10 @@ impl Picker {
         }
 
         for i in 0..self.custom_entries_len {
-            self.filter_custom_entry(i, pattern);
+            self.filter_custom_entry(i, pattern, case_matching);
         }
 
-        self.filtered_entries
-            .sort_unstable_by(|a, b| b.score.cmp(&a.score));
+        self.filtered_entries.sort_by_key(|e| Reverse(e.score));
 
         let len = self.filtered_entries.len();
         if len > 0 {
@@ -162,9 +211,14 @@ impl Picker {
         }
     }
 
-    fn filter_custom_entry(&mut self, index: usize, pattern: &str) -> bool {
+    fn filter_custom_entry(
+        &mut self,
+        index: usize,
+        pattern: &str,
+        case_matching: CaseMatching,
+    ) -> bool {
         let entry = &self.custom_entries_buffer[index];
-        let score = self.fuzzy_matcher.score(entry, pattern);
+        let score = self.fuzzy_matcher.score(entry, pattern, case_matching);
         if score == 0 {
             return false;
         }
@@ -183,14 +237,57 @@ impl Picker {
         Some((source, entry))
     }
 
+    pub fn toggle_mark(&mut self) {
+        let source = match self.cursor {
+            Some(cursor) => self.filtered_entries[cursor].source,
+            None => return,
+        };
+        match self.marked_entries.iter().position(|&s| s == source) {
+            Some(i) => {
+                self.marked_entries.remove(i);
+            }
+            None => self.marked_entries.push(source),
+        }
+    }
+
+    pub fn is_marked(&self, index: usize) -> bool {
+        let source = self.filtered_entries[index].source;
+        self.marked_entries.contains(&source)
+    }
+
+    pub fn picked_entries<'a>(
+        &'a self,
+        words: &'a WordDatabase,
+    ) -> impl 'a + Iterator<Item = (EntrySource, &'a str)> {
+        let current_entry = if self.marked_entries.is_empty() {
+            self.current_entry(words)
+        } else {
+            None
+        };
+        let custom_entries = &self.custom_entries_buffer[..];
+        let marked_entries = self.marked_entries.iter().map(move |&source| {
+            let entry = FilteredEntry { source, score: 0 };
+            (
+                source,
+                filtered_to_picker_entry(&entry, custom_entries, words),
+            )
+        });
+        current_entry.into_iter().chain(marked_entries)
+    }
+
     pub fn entries<'a>(
         &'a self,
         words: &'a WordDatabase,
-    ) -> impl 'a + ExactSizeIterator<Item = &'a str> {
+    ) -> impl 'a + ExactSizeIterator<Item = (EntryKind, &'a str)> {
         let custom_entries = &self.custom_entries_buffer[..];
-        self.filtered_entries
-            .iter()
-            .map(move |e| filtered_to_picker_entry(e, custom_entries, words))
+        let custom_entries_kinds = &self.custom_entries_kinds[..];
+        self.filtered_entries.iter().map(move |e| {
+            let kind = match e.source {
+                EntrySource::Custom(i) => custom_entries_kinds[i],
+                EntrySource::WordDatabase(_) => EntryKind::Text,
+            };
+            (kind, filtered_to_picker_entry(e, custom_entries, words))
+        })
     }
 }
 
@@ -208,6 +305,7 @@ fn filtered_to_picker_entry<'a>(
 const FIRST_CHAR_SCORE: u32 = 1;
 const WORD_BOUNDARY_MATCH_SCORE: u32 = 2;
 const CONSECUTIVE_MATCH_SCORE: u32 = 3;
+const PATH_SEGMENT_MATCH_SCORE: u32 = 4;
 
 struct FuzzyMatch {
     rest_index: u32,
@@ -220,11 +318,17 @@ struct FuzzyMatcher {
     next_matches: Vec<FuzzyMatch>,
 }
 impl FuzzyMatcher {
-    pub fn score(&mut self, text: &str, pattern: &str) -> u32 {
+    pub fn score(&mut self, text: &str, pattern: &str, case_matching: CaseMatching) -> u32 {
         if pattern.is_empty() {
             return 1;
         }
 
+        let ignore_case = match case_matching {
+            CaseMatching::Insensitive => true,
+            CaseMatching::Smart => !pattern.chars().any(char::is_uppercase),
+            CaseMatching::Sensitive => false,
+        };
+
         self.previous_matches.clear();
         self.previous_matches.push(FuzzyMatch {
             rest_index: 0,
@@ -237,11 +341,18 @@ impl FuzzyMatcher {
             for previous_match in &self.previous_matches {
                 let mut previous_text_char = '\0';
                 for (i, text_char) in text[previous_match.rest_index as usize..].char_indices() {
-                    if text_char.eq_ignore_ascii_case(&pattern_char) {
+                    let is_match = if ignore_case {
+                        text_char.eq_ignore_ascii_case(&pattern_char)
+                    } else {
+                        text_char == pattern_char
+                    };
+                    if is_match {
                         let (matched, mut score) = if i == 0 && previous_match.rest_index != 0 {
                             (true, CONSECUTIVE_MATCH_SCORE)
                         } else if !text_char.is_ascii_alphanumeric() {
                             (true, 0)
+                        } else if matches!(previous_text_char, '/' | '\\') {
+                            (true, PATH_SEGMENT_MATCH_SCORE)
                         } else {
                             let is_word_boundary = (!previous_text_char.is_ascii_alphanumeric()
                                 && text_char.is_ascii_alphanumeric())
@@ -289,60 +400,219 @@ impl FuzzyMatcher {
 mod tests {
     use super::*;
 
+    #[test]
+    fn picker_case_matching() {
+        let mut picker = Picker::default();
+        picker.add_custom_entry("open");
+        picker.add_custom_entry("Open");
+
+        picker.filter(WordIndicesIter::empty(), "OP", CaseMatching::Insensitive);
+        assert_eq!(2, picker.len());
+        picker.filter(WordIndicesIter::empty(), "op", CaseMatching::Insensitive);
+        assert_eq!(2, picker.len());
+
+        picker.filter(WordIndicesIter::empty(), "op", CaseMatching::Sensitive);
+        assert_eq!(1, picker.len());
+        assert_eq!(
+            Some((EntryKind::Text, "open")),
+            picker.entries(&WordDatabase::new()).next()
+        );
+
+        picker.filter(WordIndicesIter::empty(), "OP", CaseMatching::Smart);
+        assert_eq!(0, picker.len());
+        picker.filter(WordIndicesIter::empty(), "op", CaseMatching::Smart);
+        assert_eq!(2, picker.len());
+        picker.filter(WordIndicesIter::empty(), "Op", CaseMatching::Smart);
+        assert_eq!(1, picker.len());
+        assert_eq!(
+            Some((EntryKind::Text, "Open")),
+            picker.entries(&WordDatabase::new()).next()
+        );
+    }
+
+    #[test]
+    fn picker_multi_selection() {
+        let words = WordDatabase::new();
+        let mut picker = Picker::default();
+        picker.add_custom_entry("first");
+        picker.add_custom_entry("second");
+        picker.add_custom_entry("third");
+        picker.filter(WordIndicesIter::empty(), "", CaseMatching::Insensitive);
+        picker.move_cursor(0);
+
+        let picked: Vec<_> = picker.picked_entries(&words).map(|(_, e)| e).collect();
+        assert_eq!(vec!["first"], picked);
+
+        picker.toggle_mark();
+        picker.move_cursor(2);
+        picker.toggle_mark();
+        picker.move_cursor(-1);
+        assert!(picker.is_marked(0));
+        assert!(!picker.is_marked(1));
+        assert!(picker.is_marked(2));
+
+        picker.filter(WordIndicesIter::empty(), "d", CaseMatching::Insensitive);
+        let picked: Vec<_> = picker.picked_entries(&words).map(|(_, e)| e).collect();
+        assert_eq!(vec!["first", "third"], picked);
+
+        picker.filter(WordIndicesIter::empty(), "", CaseMatching::Insensitive);
+        picker.select_best();
+        picker.toggle_mark();
+        picker.move_cursor(1);
+        picker.toggle_mark();
+        let picked: Vec<_> = picker.picked_entries(&words).map(|(_, e)| e).collect();
+        assert_eq!(vec!["third", "second"], picked);
+
+        picker.clear();
+        assert_eq!(0, picker.picked_entries(&words).count());
+    }
+
+    #[test]
+    fn picker_path_segments() {
+        let mut matcher = FuzzyMatcher::default();
+        let segments = matcher.score("src/buffer/command.rs", "sbc", CaseMatching::Insensitive);
+        let contiguous = matcher.score("src/sbcommand.rs", "sbc", CaseMatching::Insensitive);
+        assert!(segments > contiguous);
+
+        let words = WordDatabase::new();
+        let mut picker = Picker::default();
+        picker.add_custom_entry("src/sbcommand.rs");
+        picker.add_custom_entry("src/buffer/command.rs");
+        picker.filter(WordIndicesIter::empty(), "sbc", CaseMatching::Insensitive);
+        picker.select_best();
+        assert_eq!(
+            Some("src/buffer/command.rs"),
+            picker.current_entry(&words).map(|(_, e)| e)
+        );
+    }
+
+    #[test]
+    fn picker_select_best() {
+        let words = WordDatabase::new();
+        let mut picker = Picker::default();
+        picker.add_custom_entry("fxoxoxbxaxr");
+        picker.add_custom_entry("foo_bar");
+        picker.add_custom_entry("foobar1");
+        picker.add_custom_entry("foobar2");
+
+        picker.filter(WordIndicesIter::empty(), "", CaseMatching::Insensitive);
+        picker.move_cursor(0);
+        picker.move_cursor(3);
+        assert_eq!(Some(3), picker.cursor());
+
+        picker.filter(
+            WordIndicesIter::empty(),
+            "foobar",
+            CaseMatching::Insensitive,
+        );
+        picker.select_best();
+        assert_eq!(Some(0), picker.cursor());
+        assert_eq!(
+            Some("foobar1"),
+            picker.current_entry(&words).map(|(_, e)| e)
+        );
+
+        picker.filter(
+            WordIndicesIter::empty(),
+            "missing",
+            CaseMatching::Insensitive,
+        );
+        picker.select_best();
+        assert_eq!(None, picker.cursor());
+    }
+
+    #[test]
+    fn picker_scroll_follows_height() {
+        let mut picker = Picker::default();
+        for i in 0..20 {
+            picker.add_custom_entry_fmt(format_args!("entry{}", i));
+        }
+        picker.filter(WordIndicesIter::empty(), "", CaseMatching::Insensitive);
+        picker.move_cursor(0);
+        picker.move_cursor(18);
+        assert_eq!(Some(18), picker.cursor());
+
+        assert_eq!(8, picker.update_scroll(8));
+        assert_eq!(11, picker.scroll());
+
+        assert_eq!(3, picker.update_scroll(3));
+        assert_eq!(16, picker.scroll());
+
+        picker.move_cursor(-6);
+        assert_eq!(3, picker.update_scroll(3));
+        assert_eq!(12, picker.scroll());
+
+        picker.move_cursor(6);
+        assert_eq!(20, picker.update_scroll(32));
+        assert_eq!(0, picker.scroll());
+    }
+
     #[test]
     fn fuzzy_matcher_test() {
         let mut fuzzy_matcher = FuzzyMatcher::default();
 
-        assert_eq!(1, fuzzy_matcher.score("", ""));
-        assert_eq!(1, fuzzy_matcher.score("abc", ""));
-        assert_eq!(0, fuzzy_matcher.score("", "abc"));
-        assert_eq!(0, fuzzy_matcher.score("abc", "z"));
-        assert_eq!(0, fuzzy_matcher.score("a", "xyz"));
+        assert_eq!(1, fuzzy_matcher.score("", "", CaseMatching::Insensitive));
+        assert_eq!(1, fuzzy_matcher.score("abc", "", CaseMatching::Insensitive));
+        assert_eq!(0, fuzzy_matcher.score("", "abc", CaseMatching::Insensitive));
+        assert_eq!(
+            0,
+            fuzzy_matcher.score("abc", "z", CaseMatching::Insensitive)
+        );
+        assert_eq!(
+            0,
+            fuzzy_matcher.score("a", "xyz", CaseMatching::Insensitive)
+        );
 
         assert_eq!(
             FIRST_CHAR_SCORE + WORD_BOUNDARY_MATCH_SCORE + CONSECUTIVE_MATCH_SCORE * 3 + 1,
-            fuzzy_matcher.score("word", "word"),
+            fuzzy_matcher.score("word", "word", CaseMatching::Insensitive),
         );
 
         assert_eq!(
             FIRST_CHAR_SCORE + WORD_BOUNDARY_MATCH_SCORE + CONSECUTIVE_MATCH_SCORE * 2,
-            fuzzy_matcher.score("word", "wor"),
+            fuzzy_matcher.score("word", "wor", CaseMatching::Insensitive),
         );
 
-        assert_eq!(0, fuzzy_matcher.score("word", "wrd"),);
+        assert_eq!(
+            0,
+            fuzzy_matcher.score("word", "wrd", CaseMatching::Insensitive),
+        );
 
         assert_eq!(
             FIRST_CHAR_SCORE + WORD_BOUNDARY_MATCH_SCORE + CONSECUTIVE_MATCH_SCORE,
-            fuzzy_matcher.score("first/second", "f/s")
+            fuzzy_matcher.score("first/second", "f/s", CaseMatching::Insensitive)
         );
 
         assert_eq!(
             FIRST_CHAR_SCORE + (WORD_BOUNDARY_MATCH_SCORE + CONSECUTIVE_MATCH_SCORE) * 2,
-            fuzzy_matcher.score("camelCase", "caca"),
+            fuzzy_matcher.score("camelCase", "caca", CaseMatching::Insensitive),
         );
 
         assert_eq!(
             FIRST_CHAR_SCORE + WORD_BOUNDARY_MATCH_SCORE * 3,
-            fuzzy_matcher.score("ababAbA", "aaa")
+            fuzzy_matcher.score("ababAbA", "aaa", CaseMatching::Insensitive)
         );
         assert_eq!(
             FIRST_CHAR_SCORE + WORD_BOUNDARY_MATCH_SCORE * 2,
-            fuzzy_matcher.score("abc cde", "ac"),
+            fuzzy_matcher.score("abc cde", "ac", CaseMatching::Insensitive),
+        );
+        assert_eq!(
+            WORD_BOUNDARY_MATCH_SCORE,
+            fuzzy_matcher.score("abc x", "x", CaseMatching::Insensitive)
         );
-        assert_eq!(WORD_BOUNDARY_MATCH_SCORE, fuzzy_matcher.score("abc x", "x"));
 
         assert_eq!(
             WORD_BOUNDARY_MATCH_SCORE + CONSECUTIVE_MATCH_SCORE * 3,
-            fuzzy_matcher.score("AxxBxx Abcd", "abcd")
+            fuzzy_matcher.score("AxxBxx Abcd", "abcd", CaseMatching::Insensitive)
         );
 
         assert_eq!(
             FIRST_CHAR_SCORE + WORD_BOUNDARY_MATCH_SCORE,
-            fuzzy_matcher.score("abc", "a")
+            fuzzy_matcher.score("abc", "a", CaseMatching::Insensitive)
         );
         assert_eq!(
             WORD_BOUNDARY_MATCH_SCORE,
-            fuzzy_matcher.score("xyz-abc", "a")
+            fuzzy_matcher.score("xyz-abc", "a", CaseMatching::Insensitive)
         );
 
         let repetition_count = 100;
@@ -352,7 +622,11 @@ mod tests {
                 + WORD_BOUNDARY_MATCH_SCORE
                 + CONSECUTIVE_MATCH_SCORE * (repetition_count - 1) as u32
                 + 1,
-            fuzzy_matcher.score(&big_repetitive_text, &big_repetitive_text),
+            fuzzy_matcher.score(
+                &big_repetitive_text,
+                &big_repetitive_text,
+                CaseMatching::Insensitive
+            ),
         );
     }
 }
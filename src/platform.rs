@@ -101,7 +101,7 @@ impl Platform {
     }
 
     pub fn read_from_clipboard(&self, text: &mut String) {
-        if let Some(mut command) = parse_process_command(&self.paste_command) {
+        if let Ok(mut command) = parse_process_command(&self.paste_command) {
             command.stdin(Stdio::null());
             command.stdout(Stdio::piped());
             command.stderr(Stdio::null());
@@ -119,7 +119,7 @@ impl Platform {
     }
 
     pub fn write_to_clipboard(&mut self, text: &str) {
-        if let Some(mut command) = parse_process_command(&self.copy_command) {
+        if let Ok(mut command) = parse_process_command(&self.copy_command) {
             command.stdin(Stdio::piped());
             command.stdout(Stdio::null());
             command.stderr(Stdio::null());
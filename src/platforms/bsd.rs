@@ -17,8 +17,8 @@ use pepper::{
 
 mod unix_utils;
 use unix_utils::{
-    get_terminal_size, is_pipped, parse_terminal_keys, read, read_from_connection, run,
-    suspend_process, Process, RawMode,
+    accept_connection, get_terminal_size, is_pipped, parse_terminal_keys, read,
+    read_from_connection, run, suspend_process, Process, RawMode,
 };
 
 const MAX_CLIENT_COUNT: usize = 20;
@@ -318,23 +318,20 @@ fn run_server(args: Args, listener: UnixListener) -> Result<(), AnyError> {
                 }
                 1 => {
                     for _ in 0..event_data {
-                        match listener.accept() {
-                            Ok((connection, _)) => {
-                                for (i, c) in client_connections.iter_mut().enumerate() {
-                                    if c.is_none() {
-                                        kqueue.add(
-                                            Event::Fd(connection.as_raw_fd()),
-                                            CLIENTS_START_INDEX + i,
-                                        );
-                                        *c = Some(connection);
-                                        let handle = ClientHandle::from_index(i).unwrap();
-                                        event_sender
-                                            .send(ApplicationEvent::ConnectionOpen { handle })?;
-                                        break;
-                                    }
+                        if let Some(connection) = accept_connection(&listener)? {
+                            for (i, c) in client_connections.iter_mut().enumerate() {
+                                if c.is_none() {
+                                    kqueue.add(
+                                        Event::Fd(connection.as_raw_fd()),
+                                        CLIENTS_START_INDEX + i,
+                                    );
+                                    *c = Some(connection);
+                                    let handle = ClientHandle::from_index(i).unwrap();
+                                    event_sender
+                                        .send(ApplicationEvent::ConnectionOpen { handle })?;
+                                    break;
                                 }
                             }
-                            Err(error) => panic!("could not accept connection {}", error),
                         }
                     }
                 }
@@ -464,7 +461,10 @@ fn run_client(args: Args, mut connection: UnixStream) {
                 break;
             }
             if suspend {
-                suspend_process(&mut application, &mut raw_mode);
+                let bytes = suspend_process(&mut application, &mut raw_mode);
+                if connection.write_all(bytes).is_err() {
+                    break 'main_loop;
+                }
             }
         }
     }
@@ -17,8 +17,8 @@ use pepper::{
 
 mod unix_utils;
 use unix_utils::{
-    get_terminal_size, is_pipped, parse_terminal_keys, read, read_from_connection, run,
-    suspend_process, Process, RawMode,
+    accept_connection, get_terminal_size, is_pipped, parse_terminal_keys, read,
+    read_from_connection, run, suspend_process, Process, RawMode,
 };
 
 const MAX_CLIENT_COUNT: usize = 20;
@@ -306,8 +306,8 @@ fn run_server(args: Args, listener: UnixListener) -> Result<(), AnyError> {
                         }
                     }
                 }
-                1 => match listener.accept() {
-                    Ok((connection, _)) => {
+                1 => {
+                    if let Some(connection) = accept_connection(&listener)? {
                         for (i, c) in client_connections.iter_mut().enumerate() {
                             if c.is_none() {
                                 epoll.add(connection.as_raw_fd(), CLIENTS_START_INDEX + i);
@@ -318,8 +318,7 @@ fn run_server(args: Args, listener: UnixListener) -> Result<(), AnyError> {
                             }
                         }
                     }
-                    Err(error) => panic!("could not accept connection {}", error),
-                },
+                }
                 CLIENTS_START_INDEX..=CLIENTS_LAST_INDEX => {
                     let index = event_index - CLIENTS_START_INDEX;
                     if let Some(ref mut connection) = client_connections[index] {
@@ -460,7 +459,10 @@ fn run_client(args: Args, mut connection: UnixStream) {
                 break;
             }
             if suspend {
-                suspend_process(&mut application, &mut raw_mode);
+                let bytes = suspend_process(&mut application, &mut raw_mode);
+                if connection.write_all(bytes).is_err() {
+                    break 'main_loop;
+                }
             }
         }
     }
@@ -143,6 +143,16 @@ pub fn read(fd: RawFd, buf: &mut [u8]) -> Result<usize, ()> {
     }
 }
 
+pub fn accept_connection(listener: &UnixListener) -> io::Result<Option<UnixStream>> {
+    match listener.accept() {
+        Ok((connection, _)) => Ok(Some(connection)),
+        Err(error) => match error.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => Ok(None),
+            _ => Err(error),
+        },
+    }
+}
+
 pub fn read_from_connection(
     connection: &mut UnixStream,
     buf_pool: &mut BufPool,
@@ -241,17 +251,37 @@ impl Drop for Process {
     }
 }
 
-pub fn suspend_process(application: &mut ClientApplication, raw_mode: &mut Option<RawMode>) {
+pub fn suspend_process<'a>(
+    application: &'a mut ClientApplication,
+    raw_mode: &mut Option<RawMode>,
+) -> &'a [u8] {
     application.restore_screen();
     let was_in_raw_mode = raw_mode.is_some();
     *raw_mode = None;
 
     unsafe { libc::raise(libc::SIGTSTP) };
 
-    if was_in_raw_mode {
+    let terminal_size = if was_in_raw_mode {
         *raw_mode = Some(RawMode::enter());
-    }
+        Some(get_terminal_size())
+    } else {
+        None
+    };
     application.reinit_screen();
+    resume_process(application, terminal_size)
+}
+
+// the terminal may have been resized while suspended so we report its current size which also
+// makes the server redraw everything. when there was no terminal in raw mode (stdin is pipped)
+// there is no job control to resume from and this produces no bytes to send
+fn resume_process<'a>(
+    application: &'a mut ClientApplication,
+    terminal_size: Option<(usize, usize)>,
+) -> &'a [u8] {
+    match terminal_size {
+        Some(size) => application.update(Some(size), &[], &[], &[]).1,
+        None => &[],
+    }
 }
 
 pub fn get_terminal_size() -> (usize, usize) {
@@ -317,3 +347,42 @@ pub fn parse_terminal_keys(mut buf: &[u8], backspace_code: u8, keys: &mut Vec<Ke
         keys.push(key);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pepper::{events::ClientEvent, serialization::Serialize};
+
+    #[test]
+    fn accept_connection_without_pending_client() {
+        let path = env::temp_dir().join("pepper_accept_connection_test.sock");
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        listener.set_nonblocking(true).unwrap();
+
+        let pending = accept_connection(&listener);
+        let client = UnixStream::connect(&path);
+        let accepted = accept_connection(&listener);
+        let _ = fs::remove_file(&path);
+
+        assert!(matches!(pending, Ok(None)));
+        assert!(client.is_ok());
+        assert!(matches!(accepted, Ok(Some(_))));
+    }
+
+    #[test]
+    fn resume_process_sends_resize() {
+        let stdout = io::stdout();
+        let mut application = ClientApplication::new(stdout.lock(), true);
+
+        let mut bytes = resume_process(&mut application, Some((80, 24)));
+        match ClientEvent::deserialize(&mut bytes) {
+            Ok(ClientEvent::Resize(80, 24)) => (),
+            _ => panic!("expected a resize event"),
+        }
+        assert!(bytes.is_empty());
+
+        assert!(resume_process(&mut application, None).is_empty());
+    }
+}
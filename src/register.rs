@@ -28,6 +28,7 @@ const REGISTERS_LEN: usize = (b'z' - b'a' + 1) as _;
 
 pub struct RegisterCollection {
     registers: [String; REGISTERS_LEN],
+    linewise: [bool; REGISTERS_LEN],
 }
 
 impl RegisterCollection {
@@ -35,6 +36,7 @@ impl RegisterCollection {
         const DEFAULT_STRING: String = String::new();
         Self {
             registers: [DEFAULT_STRING; REGISTERS_LEN],
+            linewise: [false; REGISTERS_LEN],
         }
     }
 
@@ -43,6 +45,15 @@ impl RegisterCollection {
     }
 
     pub fn get_mut(&mut self, key: RegisterKey) -> &mut String {
+        self.linewise[key.0 as usize] = false;
         &mut self.registers[key.0 as usize]
     }
+
+    pub fn is_linewise(&self, key: RegisterKey) -> bool {
+        self.linewise[key.0 as usize]
+    }
+
+    pub fn set_linewise(&mut self, key: RegisterKey, linewise: bool) {
+        self.linewise[key.0 as usize] = linewise;
+    }
 }
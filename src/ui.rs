@@ -405,11 +405,7 @@ fn draw_picker(ctx: &RenderContext, buf: &mut Vec<u8>) {
     let scroll = ctx.editor.picker.scroll();
 
     let width = ctx.viewport_size.0 as _;
-    let height = ctx
-        .editor
-        .picker
-        .len()
-        .min(ctx.editor.config.picker_max_height as _);
+    let height = (ctx.viewport_size.1 as usize).saturating_sub(1 + ctx.draw_height as usize);
 
     let background_normal_color = ctx.editor.theme.statusbar_inactive_background;
     let background_selected_color = ctx.editor.theme.statusbar_active_background;
@@ -418,7 +414,7 @@ fn draw_picker(ctx: &RenderContext, buf: &mut Vec<u8>) {
     set_background_color(buf, background_normal_color);
     set_foreground_color(buf, foreground_color);
 
-    for (i, entry) in ctx
+    for (i, (kind, entry)) in ctx
         .editor
         .picker
         .entries(&ctx.editor.word_database)
@@ -431,6 +427,10 @@ fn draw_picker(ctx: &RenderContext, buf: &mut Vec<u8>) {
         } else if i == cursor + 1 {
             set_background_color(buf, background_normal_color);
         }
+        let marked = ctx.editor.picker.is_marked(i);
+        if marked {
+            set_foreground_color(buf, ctx.editor.theme.token_keyword);
+        }
 
         let mut x = 0;
 
@@ -444,8 +444,16 @@ fn draw_picker(ctx: &RenderContext, buf: &mut Vec<u8>) {
             }
         }
 
+        let hint = kind.hint();
+        let hint_width = if !hint.is_empty() && hint.len() + 8 < width {
+            hint.len() + 1
+        } else {
+            0
+        };
+        let name_width = width - hint_width;
+
         let name_char_count = entry.chars().count();
-        if name_char_count < width {
+        if name_char_count < name_width {
             for c in entry.chars() {
                 print_char(buf, &mut x, c);
             }
@@ -453,12 +461,24 @@ fn draw_picker(ctx: &RenderContext, buf: &mut Vec<u8>) {
             buf.extend_from_slice(b"...");
             x += 3;
             let name_char_count = name_char_count + 3;
-            for c in entry.chars().skip(name_char_count.saturating_sub(width)) {
+            for c in entry
+                .chars()
+                .skip(name_char_count.saturating_sub(name_width))
+            {
                 print_char(buf, &mut x, c);
             }
         }
-        for _ in x..width {
+        for _ in x..name_width {
+            buf.push(b' ');
+        }
+        if marked {
+            set_foreground_color(buf, foreground_color);
+        }
+        if hint_width > 0 {
+            set_foreground_color(buf, ctx.editor.theme.token_comment);
             buf.push(b' ');
+            buf.extend_from_slice(hint.as_bytes());
+            set_foreground_color(buf, foreground_color);
         }
         x = 0;
 